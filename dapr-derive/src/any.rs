@@ -0,0 +1,95 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use crate::args::TypeAttrs;
+
+/// The stable type name in the `type_url`, the type name with its module path by default.
+fn type_name(input: &syn::DeriveInput, attrs: &TypeAttrs) -> TokenStream {
+    match attrs.name {
+        Some(ref name) => quote! { #name },
+        None => {
+            let ident = input.ident.to_string();
+
+            quote! { concat!(module_path!(), "::", #ident) }
+        }
+    }
+}
+
+pub fn into_any(input: &syn::DeriveInput, attrs: &TypeAttrs) -> TokenStream {
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name = type_name(input, attrs);
+    let codec = attrs.codec;
+    let encode = if codec.is_serde() {
+        quote! {
+            fn try_into_any(self) -> Result<Option<::dapr::prost_types::Any>, ::dapr::any::Error> {
+                ::dapr::codec::encode_named(#codec, #name, &self).map(Some)
+            }
+        }
+    } else {
+        // the Protobuf messages may be named as the other Dapr SDKs do
+        quote! {
+            fn try_into_any(self) -> Result<Option<::dapr::prost_types::Any>, ::dapr::any::Error> {
+                ::dapr::any::IntoAny::try_into_any_with(self, ::dapr::any::Encoding::Rust)
+            }
+
+            fn try_into_any_with(
+                self,
+                encoding: ::dapr::any::Encoding,
+            ) -> Result<Option<::dapr::prost_types::Any>, ::dapr::any::Error> {
+                ::dapr::codec::encode_message_named(encoding, #name, &self).map(Some)
+            }
+        }
+    };
+
+    quote! {
+        impl #impl_generics ::dapr::any::IntoAny for #ident #ty_generics #where_clause {
+            #encode
+        }
+
+        impl #impl_generics ::dapr::any::IntoElement for #ident #ty_generics #where_clause {}
+    }
+}
+
+pub fn try_from_any(input: &syn::DeriveInput, attrs: &TypeAttrs) -> TokenStream {
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name = type_name(input, attrs);
+    let codec = attrs.codec;
+    let (decode, decode_lenient) = if codec.is_serde() {
+        (
+            quote! { ::dapr::codec::decode(__codec, &any) },
+            quote! { ::dapr::codec::decode(::dapr::codec::Codec::detect(&any, #codec), &any) },
+        )
+    } else {
+        let decode = quote! { ::dapr::codec::decode_message(&any) };
+
+        (
+            quote! {
+                match __codec {
+                    ::dapr::codec::Codec::Protobuf => #decode,
+                    __codec => Err(::dapr::any::Error::UnsupportedCodec(__codec.to_string())),
+                }
+            },
+            decode,
+        )
+    };
+
+    quote! {
+        impl #impl_generics ::dapr::any::TryFromAny for #ident #ty_generics #where_clause {
+            type Error = ::dapr::any::Error;
+
+            fn try_from(any: ::dapr::prost_types::Any) -> Result<Self, Self::Error> {
+                let __codec = ::dapr::codec::check_type_url(&any, #name)?;
+
+                #decode
+            }
+
+            fn try_from_lenient(any: ::dapr::prost_types::Any) -> Result<Self, Self::Error> {
+                #decode_lenient
+            }
+        }
+
+        impl #impl_generics ::dapr::any::FromElement for #ident #ty_generics #where_clause {}
+    }
+}
@@ -0,0 +1,374 @@
+use case::CaseExt;
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::{AttributeArgs, Lit, Meta, MetaNameValue, NestedMeta};
+
+/// The arguments of `#[dapr::service]`.
+#[derive(Clone, Debug, Default)]
+pub struct ServiceArgs {
+    /// The service handles the topic events with `dapr::client::Events`.
+    pub events: bool,
+    /// The service handles the binding events with `dapr::client::Bindings`.
+    pub bindings: bool,
+    /// Rename all the methods with the rule.
+    pub rename_all: Option<RenameRule>,
+    /// The wire codec of the arguments and results.
+    pub codec: Codec,
+    /// Describe the methods with `dapr::schema::DescribeService`.
+    pub schema: bool,
+}
+
+impl ServiceArgs {
+    pub fn parse(args: AttributeArgs, item: &syn::ItemTrait) -> syn::Result<Self> {
+        let mut service_args = ServiceArgs::default();
+
+        for arg in args {
+            match arg {
+                NestedMeta::Meta(Meta::Path(ref path)) if path.is_ident("events") => {
+                    service_args.events = true
+                }
+                NestedMeta::Meta(Meta::Path(ref path)) if path.is_ident("bindings") => {
+                    service_args.bindings = true
+                }
+                NestedMeta::Meta(Meta::Path(ref path)) if path.is_ident("schema") => {
+                    service_args.schema = true
+                }
+                NestedMeta::Meta(Meta::NameValue(ref meta)) if meta.path.is_ident("rename_all") => {
+                    service_args.rename_all = Some(RenameRule::parse(meta)?)
+                }
+                NestedMeta::Meta(Meta::NameValue(ref meta)) if meta.path.is_ident("codec") => {
+                    service_args.codec = Codec::parse(meta)?
+                }
+                _ => return Err(syn::Error::new_spanned(arg, "unknown service argument")),
+            }
+        }
+
+        // the service opts into the capabilities with the supertraits
+        for supertrait in &item.supertraits {
+            if let syn::TypeParamBound::Trait(syn::TraitBound { ref path, .. }) = supertrait {
+                match path.segments.last() {
+                    Some(segment) if segment.ident == "Events" => service_args.events = true,
+                    Some(segment) if segment.ident == "Bindings" => service_args.bindings = true,
+                    _ => {}
+                }
+            }
+        }
+
+        validate_methods(item, service_args.codec)?;
+
+        // the schema describes the JSON arguments and results
+        if service_args.schema && !service_args.codec.is_serde() {
+            return Err(syn::Error::new_spanned(
+                &item.ident,
+                "the `schema` is not supported with the `protobuf` codec",
+            ));
+        }
+
+        // the methods are dispatched by names, which must be unique
+        let mut names = std::collections::HashSet::new();
+
+        for item in &item.items {
+            if let syn::TraitItem::Method(method) = item {
+                let name = method_name(method, service_args.rename_all);
+
+                for name in Some(name).into_iter().chain(method_aliases(method)) {
+                    if !names.insert(name.clone()) {
+                        return Err(syn::Error::new_spanned(
+                            &method.sig.ident,
+                            format!("duplicate method name `{}`", name),
+                        ));
+                    }
+                }
+            }
+        }
+
+        Ok(service_args)
+    }
+}
+
+/// The arguments of `#[dapr::stub]`.
+#[derive(Clone, Debug, Default)]
+pub struct StubArgs {
+    /// Rename all the methods with the rule.
+    pub rename_all: Option<RenameRule>,
+    /// The wire codec of the arguments and results.
+    pub codec: Codec,
+}
+
+impl StubArgs {
+    pub fn parse(args: AttributeArgs, item: &syn::ItemTrait) -> syn::Result<Self> {
+        let mut stub_args = StubArgs::default();
+
+        for arg in args {
+            match arg {
+                NestedMeta::Meta(Meta::NameValue(ref meta)) if meta.path.is_ident("rename_all") => {
+                    stub_args.rename_all = Some(RenameRule::parse(meta)?)
+                }
+                NestedMeta::Meta(Meta::NameValue(ref meta)) if meta.path.is_ident("codec") => {
+                    stub_args.codec = Codec::parse(meta)?
+                }
+                _ => return Err(syn::Error::new_spanned(arg, "unknown stub argument")),
+            }
+        }
+
+        validate_methods(item, stub_args.codec)?;
+
+        Ok(stub_args)
+    }
+}
+
+/// The rule to rename the methods, e.g. `#[dapr::service(rename_all = "camelCase")]`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RenameRule {
+    Lower,
+    Upper,
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+}
+
+impl RenameRule {
+    fn parse(meta: &MetaNameValue) -> syn::Result<Self> {
+        match meta.lit {
+            Lit::Str(ref s) => match s.value().as_str() {
+                "lowercase" => Ok(RenameRule::Lower),
+                "UPPERCASE" => Ok(RenameRule::Upper),
+                "PascalCase" => Ok(RenameRule::Pascal),
+                "camelCase" => Ok(RenameRule::Camel),
+                "snake_case" => Ok(RenameRule::Snake),
+                "SCREAMING_SNAKE_CASE" => Ok(RenameRule::ScreamingSnake),
+                "kebab-case" => Ok(RenameRule::Kebab),
+                _ => Err(syn::Error::new_spanned(s, "unknown rename rule")),
+            },
+            ref lit => Err(syn::Error::new_spanned(lit, "expected string literal")),
+        }
+    }
+
+    /// Rename the `snake_case` method name.
+    pub fn apply(self, name: &str) -> String {
+        match self {
+            RenameRule::Lower | RenameRule::Snake => name.to_owned(),
+            RenameRule::Upper | RenameRule::ScreamingSnake => name.to_ascii_uppercase(),
+            RenameRule::Pascal => name.to_camel(),
+            RenameRule::Camel => name.to_camel_lowercase(),
+            RenameRule::Kebab => name.replace('_', "-"),
+        }
+    }
+}
+
+/// The wire codec of the arguments and results, e.g. `#[dapr::service(codec = "msgpack")]`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Codec {
+    #[default]
+    Json,
+    Protobuf,
+    MsgPack,
+    Cbor,
+}
+
+impl Codec {
+    fn parse(meta: &MetaNameValue) -> syn::Result<Self> {
+        match meta.lit {
+            Lit::Str(ref s) => match s.value().as_str() {
+                "json" => Ok(Codec::Json),
+                "protobuf" => Ok(Codec::Protobuf),
+                "msgpack" => Ok(Codec::MsgPack),
+                "cbor" => Ok(Codec::Cbor),
+                _ => Err(syn::Error::new_spanned(s, "unknown codec")),
+            },
+            ref lit => Err(syn::Error::new_spanned(lit, "expected string literal")),
+        }
+    }
+
+    /// The arguments and results are serialized with `serde` instead of `prost`.
+    pub fn is_serde(self) -> bool {
+        self != Codec::Protobuf
+    }
+}
+
+impl ToTokens for Codec {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        tokens.extend(match self {
+            // the serde codecs are gated by the features of `dapr`
+            Codec::Json => quote! { ::dapr::codec::features::JSON },
+            Codec::Protobuf => quote! { ::dapr::codec::Codec::Protobuf },
+            Codec::MsgPack => quote! { ::dapr::codec::features::MSGPACK },
+            Codec::Cbor => quote! { ::dapr::codec::features::CBOR },
+        })
+    }
+}
+
+/// The attributes of trait method, e.g. `#[dapr(name = "v2/orders", alias = "orders")]`.
+#[derive(Clone, Debug, Default)]
+pub struct MethodAttrs {
+    /// The method name on the wire.
+    pub name: Option<String>,
+    /// The old names accepted by the service.
+    pub aliases: Vec<String>,
+}
+
+impl MethodAttrs {
+    pub fn parse(method: &syn::TraitItemMethod) -> syn::Result<Self> {
+        let mut attrs = MethodAttrs::default();
+
+        for attr in method.attrs.iter().filter(|attr| is_dapr_attr(attr)) {
+            let metas = match attr.parse_meta()? {
+                Meta::List(list) => list.nested,
+                meta => return Err(syn::Error::new_spanned(meta, "expected #[dapr(...)]")),
+            };
+
+            for meta in metas {
+                match meta {
+                    NestedMeta::Meta(Meta::NameValue(MetaNameValue {
+                        ref path,
+                        lit: Lit::Str(ref s),
+                        ..
+                    })) if path.is_ident("name") => attrs.name = Some(s.value()),
+                    NestedMeta::Meta(Meta::NameValue(MetaNameValue {
+                        ref path,
+                        lit: Lit::Str(ref s),
+                        ..
+                    })) if path.is_ident("alias") => attrs.aliases.push(s.value()),
+                    _ => return Err(syn::Error::new_spanned(meta, "unknown method attribute")),
+                }
+            }
+        }
+
+        Ok(attrs)
+    }
+}
+
+/// The attributes of the type deriving `IntoAny` and `TryFromAny`, e.g. `#[dapr(codec = "json", name = "shop.Order")]`.
+#[derive(Clone, Debug, Default)]
+pub struct TypeAttrs {
+    /// The codec of the value.
+    pub codec: Codec,
+    /// The stable type name in the `type_url`.
+    pub name: Option<String>,
+}
+
+impl TypeAttrs {
+    pub fn parse(input: &syn::DeriveInput) -> syn::Result<Self> {
+        let mut attrs = TypeAttrs::default();
+
+        for attr in input.attrs.iter().filter(|attr| is_dapr_attr(attr)) {
+            let metas = match attr.parse_meta()? {
+                Meta::List(list) => list.nested,
+                meta => return Err(syn::Error::new_spanned(meta, "expected #[dapr(...)]")),
+            };
+
+            for meta in metas {
+                match meta {
+                    NestedMeta::Meta(Meta::NameValue(ref meta)) if meta.path.is_ident("codec") => {
+                        attrs.codec = Codec::parse(meta)?
+                    }
+                    NestedMeta::Meta(Meta::NameValue(MetaNameValue {
+                        ref path,
+                        lit: Lit::Str(ref s),
+                        ..
+                    })) if path.is_ident("name") => attrs.name = Some(s.value()),
+                    _ => return Err(syn::Error::new_spanned(meta, "unknown type attribute")),
+                }
+            }
+        }
+
+        // the instances of a generic type would share the default name
+        if attrs.name.is_none()
+            && input
+                .generics
+                .params
+                .iter()
+                .any(|param| !matches!(param, syn::GenericParam::Lifetime(_)))
+        {
+            return Err(syn::Error::new_spanned(
+                &input.generics,
+                "generic types must be named with `#[dapr(name = \"...\")]`",
+            ));
+        }
+
+        Ok(attrs)
+    }
+}
+
+/// The method name on the wire, the `#[dapr(name = "...")]` takes precedence over the `rename_all` rule.
+pub fn method_name(method: &syn::TraitItemMethod, rename_all: Option<RenameRule>) -> String {
+    let attrs = MethodAttrs::parse(method).unwrap_or_default();
+
+    attrs.name.unwrap_or_else(|| {
+        let name = method.sig.ident.to_string();
+
+        match rename_all {
+            Some(rule) => rule.apply(&name),
+            None => name,
+        }
+    })
+}
+
+/// The old method names accepted by the service.
+pub fn method_aliases(method: &syn::TraitItemMethod) -> Vec<String> {
+    MethodAttrs::parse(method).unwrap_or_default().aliases
+}
+
+/// Remove the `#[dapr(...)]` attributes from the trait methods.
+pub fn strip_attrs(item: &mut syn::ItemTrait) {
+    for item in &mut item.items {
+        if let syn::TraitItem::Method(method) = item {
+            method.attrs.retain(|attr| !is_dapr_attr(attr));
+        }
+    }
+}
+
+fn is_dapr_attr(attr: &syn::Attribute) -> bool {
+    attr.path.is_ident("dapr")
+}
+
+fn validate_methods(item: &syn::ItemTrait, codec: Codec) -> syn::Result<()> {
+    for item in &item.items {
+        if let syn::TraitItem::Method(method) = item {
+            MethodAttrs::parse(method)?;
+
+            if let Some(ty) = crate::client::method_output(method).filter(|ty| is_result_alias(ty))
+            {
+                return Err(syn::Error::new_spanned(
+                    ty,
+                    "expected `Result<T, E>` with both the `Ok` and `Err` types, \
+                     `Result<T>` aliases like `std::io::Result<T>` are not supported",
+                ));
+            }
+
+            // the Protobuf message is passed as-is, without the arguments wrapper
+            if !codec.is_serde() && crate::client::method_args(method).count() > 1 {
+                return Err(syn::Error::new_spanned(
+                    &method.sig,
+                    "the `protobuf` codec takes at most one message argument",
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The type is a `Result<T>` alias, whose `Err` type is not spelled out.
+fn is_result_alias(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Paren(syn::TypeParen { elem, .. })
+        | syn::Type::Group(syn::TypeGroup { elem, .. }) => is_result_alias(elem),
+        syn::Type::Path(syn::TypePath { path, .. }) => match path.segments.last() {
+            Some(segment) if segment.ident == "Result" => match segment.arguments {
+                syn::PathArguments::AngleBracketed(ref args) => {
+                    args.args
+                        .iter()
+                        .filter(|arg| matches!(arg, syn::GenericArgument::Type(_)))
+                        .count()
+                        == 1
+                }
+                _ => false,
+            },
+            _ => false,
+        },
+        _ => false,
+    }
+}
@@ -3,13 +3,40 @@ use proc_macro2::TokenStream;
 use quote::{format_ident, quote};
 use syn::{Ident, ItemTrait, TraitItem};
 
-pub fn dapr_client(item: &ItemTrait) -> TokenStream {
+use crate::args::{method_aliases, method_name, strip_attrs, Codec, RenameRule, ServiceArgs};
+
+/// The service trait without the `#[dapr(...)]` attributes.
+pub fn service_trait(item: &ItemTrait) -> TokenStream {
+    let mut item = item.clone();
+    strip_attrs(&mut item);
+
+    // the `async fn` must return `Send` futures to be awaited in the server
+    let async_trait = if item.items.iter().any(|item| match item {
+        TraitItem::Method(method) => method.sig.asyncness.is_some(),
+        _ => false,
+    }) {
+        Some(quote! { #[::dapr::async_trait] })
+    } else {
+        None
+    };
+
+    quote! {
+        #async_trait
+        #item
+    }
+}
+
+pub fn dapr_client(item: &ItemTrait, args: &ServiceArgs) -> TokenStream {
     let syn::ItemTrait { ident, items, .. } = item;
     let trait_name = &ident;
     let client_name = format_ident!("{}Client", trait_name);
 
     let impl_client = quote! {
         impl<T> #client_name<T> {
+            pub fn new(service: T) -> Self {
+                #client_name(service)
+            }
+
             pub fn into_inner(self) -> T {
                 self.0
             }
@@ -41,27 +68,74 @@ pub fn dapr_client(item: &ItemTrait) -> TokenStream {
         }
     });
 
-    let arg_types = arg_types(trait_name, methods.clone());
-    let result_types = result_types(trait_name, methods.clone());
-    let on_invoke = on_invoke(trait_name, methods.clone());
+    let arg_types = arg_types(trait_name, methods.clone(), args.codec, args.schema);
+    let result_types = result_types(trait_name, methods.clone(), args.codec, args.schema);
+    let describe = if args.schema {
+        Some(describe_service(
+            trait_name,
+            &client_name,
+            methods.clone(),
+            args.rename_all,
+        ))
+    } else {
+        None
+    };
+    let service_methods = service_methods(&client_name, methods.clone(), args.rename_all);
+    let on_invoke = on_invoke(trait_name, methods.clone(), args.rename_all, args.codec);
+
+    let events = events(args.events);
+    let bindings = bindings(args.bindings);
+    let events_bound = if args.events {
+        Some(quote! { T: ::dapr::client::Events, })
+    } else {
+        None
+    };
+    let bindings_bound = if args.bindings {
+        Some(quote! { T: ::dapr::client::Bindings, })
+    } else {
+        None
+    };
 
-    let get_topic_subscriptions = quote! {
-        async fn get_topic_subscriptions(
-            &self,
-            _request: ::dapr::tonic::Request<()>,
-        ) -> Result<::dapr::tonic::Response<::dapr::client::GetTopicSubscriptionsEnvelope>, ::dapr::tonic::Status> {
+    quote! {
+        pub struct #client_name<T>(T);
+
+        #impl_client
+        #impl_deref
+        #impl_deref_mut
+
+        #arg_types
+        #result_types
+        #describe
+        #service_methods
+
+        #[::dapr::tonic::async_trait]
+        impl<T: #trait_name> ::dapr::client::server::DaprClient for #client_name<T>
+        where
+            T: Send + Sync + 'static,
+            #events_bound
+            #bindings_bound
+        {
+            #on_invoke
+            #events
+            #bindings
+        }
+    }
+}
+
+fn events(enabled: bool) -> TokenStream {
+    let get_topic_subscriptions = if enabled {
+        quote! {
             let topics = self.0.topic_subscriptions().await.map_err(|err| {
                 ::dapr::tonic::Status::new(::dapr::tonic::Code::Internal, err.to_string())
             })?;
-
-            Ok(::dapr::tonic::Response::new(::dapr::client::GetTopicSubscriptionsEnvelope { topics }))
+        }
+    } else {
+        quote! {
+            let topics = vec![];
         }
     };
-    let on_topic_event = quote! {
-        async fn on_topic_event(
-            &self,
-            request: ::dapr::tonic::Request<::dapr::client::CloudEventEnvelope>,
-        ) -> Result<::dapr::tonic::Response<()>, ::dapr::tonic::Status> {
+    let on_topic_event = if enabled {
+        quote! {
             self.0
                 .on_topic_event(request.into_inner())
                 .await
@@ -70,24 +144,45 @@ pub fn dapr_client(item: &ItemTrait) -> TokenStream {
                     ::dapr::tonic::Status::new(::dapr::tonic::Code::Internal, err.to_string())
                 })
         }
+    } else {
+        quote! {
+            Err(::dapr::tonic::Status::unimplemented("Not yet implemented"))
+        }
     };
-    let get_bindings_subscriptions = quote! {
-        async fn get_bindings_subscriptions(
+
+    quote! {
+        async fn get_topic_subscriptions(
             &self,
             _request: ::dapr::tonic::Request<()>,
-        ) -> Result<::dapr::tonic::Response<::dapr::client::GetBindingsSubscriptionsEnvelope>, ::dapr::tonic::Status> {
+        ) -> Result<::dapr::tonic::Response<::dapr::client::GetTopicSubscriptionsEnvelope>, ::dapr::tonic::Status> {
+            #get_topic_subscriptions
+
+            Ok(::dapr::tonic::Response::new(::dapr::client::GetTopicSubscriptionsEnvelope { topics }))
+        }
+
+        async fn on_topic_event(
+            &self,
+            request: ::dapr::tonic::Request<::dapr::client::CloudEventEnvelope>,
+        ) -> Result<::dapr::tonic::Response<()>, ::dapr::tonic::Status> {
+            #on_topic_event
+        }
+    }
+}
+
+fn bindings(enabled: bool) -> TokenStream {
+    let get_bindings_subscriptions = if enabled {
+        quote! {
             let bindings = self.0.bindings_subscriptions().await.map_err(|err| {
                 ::dapr::tonic::Status::new(::dapr::tonic::Code::Internal, err.to_string())
             })?;
-
-            Ok(::dapr::tonic::Response::new(::dapr::client::GetBindingsSubscriptionsEnvelope { bindings }))
+        }
+    } else {
+        quote! {
+            let bindings = vec![];
         }
     };
-    let on_binding_event = quote! {
-        async fn on_binding_event(
-            &self,
-            request: ::dapr::tonic::Request<::dapr::client::BindingEventEnvelope>,
-        ) -> Result<::dapr::tonic::Response<::dapr::client::BindingResponseEnvelope>, ::dapr::tonic::Status> {
+    let on_binding_event = if enabled {
+        quote! {
             self.0
                 .on_binding_event(request.into_inner())
                 .await
@@ -96,48 +191,62 @@ pub fn dapr_client(item: &ItemTrait) -> TokenStream {
                     ::dapr::tonic::Status::new(::dapr::tonic::Code::Internal, err.to_string())
                 })
         }
+    } else {
+        quote! {
+            Err(::dapr::tonic::Status::unimplemented("Not yet implemented"))
+        }
     };
 
     quote! {
-        pub struct #client_name<T>(T);
-
-        #impl_client
-        #impl_deref
-        #impl_deref_mut
+        async fn get_bindings_subscriptions(
+            &self,
+            _request: ::dapr::tonic::Request<()>,
+        ) -> Result<::dapr::tonic::Response<::dapr::client::GetBindingsSubscriptionsEnvelope>, ::dapr::tonic::Status> {
+            #get_bindings_subscriptions
 
-        #arg_types
-        #result_types
+            Ok(::dapr::tonic::Response::new(::dapr::client::GetBindingsSubscriptionsEnvelope { bindings }))
+        }
 
-        #[::dapr::tonic::async_trait]
-        impl<T: #trait_name> ::dapr::client::server::DaprClient for #client_name<T>
-        where
-            T: Send + Sync + 'static,
-        {
-            #on_invoke
-            #get_topic_subscriptions
-            #on_topic_event
-            #get_bindings_subscriptions
+        async fn on_binding_event(
+            &self,
+            request: ::dapr::tonic::Request<::dapr::client::BindingEventEnvelope>,
+        ) -> Result<::dapr::tonic::Response<::dapr::client::BindingResponseEnvelope>, ::dapr::tonic::Status> {
             #on_binding_event
         }
     }
 }
 
+/// The wrappers of the serialized arguments, the Protobuf message is passed without wrapper.
+///
+/// The wrappers derive `JsonSchema` when the service is described with `schema`.
 pub fn arg_types<'a>(
     trait_name: &Ident,
     methods: impl Iterator<Item = &'a syn::TraitItemMethod>,
+    codec: Codec,
+    schema: bool,
 ) -> TokenStream {
+    if !codec.is_serde() {
+        return TokenStream::new();
+    }
+
+    let derive_schema = if schema {
+        Some(quote! {
+            #[derive(::dapr::schemars::JsonSchema)]
+            #[schemars(crate = "::dapr::schemars")]
+        })
+    } else {
+        None
+    };
+
     let types = methods.map(|method| {
         let name = arg_type_name(trait_name, method);
-        let fields = method.sig.inputs.iter().filter_map(|arg| {
-            if let syn::FnArg::Typed(syn::PatType { pat, ty, .. }) = arg {
-                Some(quote! { pub #pat : #ty })
-            } else {
-                None
-            }
+        let fields = method_args(method).map(|syn::PatType { pat, ty, .. }| {
+            quote! { pub #pat : #ty }
         });
 
         quote! {
             #[derive(Default, ::dapr::serde::Serialize, ::dapr::serde::Deserialize)]
+            #derive_schema
             struct #name { #(#fields),* }
         }
     });
@@ -147,19 +256,35 @@ pub fn arg_types<'a>(
     }
 }
 
+/// The wrappers of the serialized results, the Protobuf message is passed without wrapper.
+///
+/// The wrappers derive `JsonSchema` when the service is described with `schema`.
 pub fn result_types<'a>(
     trait_name: &Ident,
     methods: impl Iterator<Item = &'a syn::TraitItemMethod>,
+    codec: Codec,
+    schema: bool,
 ) -> TokenStream {
+    if !codec.is_serde() {
+        return TokenStream::new();
+    }
+
+    let derive_schema = if schema {
+        Some(quote! {
+            #[derive(::dapr::schemars::JsonSchema)]
+            #[schemars(crate = "::dapr::schemars")]
+        })
+    } else {
+        None
+    };
+
     let types = methods.map(|method| {
         let name = result_type_name(trait_name, method);
-        let output = match method.sig.output {
-            syn::ReturnType::Default => None,
-            syn::ReturnType::Type(_, ref ty) => Some(ty),
-        };
+        let output = method_output(method).map(|ty| result_output(ty).map_or(ty, |(ok, _)| ok));
 
         quote! {
             #[derive(Default, ::dapr::serde::Serialize, ::dapr::serde::Deserialize)]
+            #derive_schema
             struct #name((#output));
         }
     });
@@ -169,11 +294,64 @@ pub fn result_types<'a>(
     }
 }
 
+/// The method names and aliases, which are dispatched by `dapr::Router`.
+fn service_methods<'a>(
+    client_name: &Ident,
+    methods: impl Iterator<Item = &'a syn::TraitItemMethod>,
+    rename_all: Option<RenameRule>,
+) -> TokenStream {
+    let names = methods.flat_map(|method| {
+        Some(method_name(method, rename_all))
+            .into_iter()
+            .chain(method_aliases(method))
+    });
+
+    quote! {
+        impl<T> ::dapr::router::ServiceMethods for #client_name<T> {
+            fn methods() -> &'static [&'static str] {
+                &[#(#names),*]
+            }
+        }
+    }
+}
+
+fn describe_service<'a>(
+    trait_name: &Ident,
+    client_name: &Ident,
+    methods: impl Iterator<Item = &'a syn::TraitItemMethod>,
+    rename_all: Option<RenameRule>,
+) -> TokenStream {
+    let service_name = trait_name.to_string();
+    let methods = methods.map(|method| {
+        let args = arg_type_name(trait_name, method);
+        let result = result_type_name(trait_name, method);
+        let name = method_name(method, rename_all);
+        let aliases = method_aliases(method);
+
+        quote! {
+            ::dapr::schema::MethodSchema::new::<#args, #result>(&mut gen, #name, &[#(#aliases),*])
+        }
+    });
+
+    quote! {
+        impl<T> ::dapr::schema::DescribeService for #client_name<T> {
+            fn schema() -> ::dapr::schema::ServiceSchema {
+                let mut gen = ::dapr::schema::generator();
+                let methods = vec![#(#methods),*];
+
+                ::dapr::schema::ServiceSchema::new(#service_name, methods, gen)
+            }
+        }
+    }
+}
+
 fn on_invoke<'a>(
     trait_name: &Ident,
     methods: impl Iterator<Item = &'a syn::TraitItemMethod>,
+    rename_all: Option<RenameRule>,
+    codec: Codec,
 ) -> TokenStream {
-    let methods = methods.map(|method| invoke_method(trait_name, method));
+    let methods = methods.map(|method| invoke_method(trait_name, method, rename_all, codec));
 
     quote! {
         async fn on_invoke(
@@ -190,50 +368,123 @@ fn on_invoke<'a>(
     }
 }
 
-fn invoke_method(trait_name: &Ident, method: &syn::TraitItemMethod) -> TokenStream {
-    let method_name = &method.sig.ident;
-    let args = method.sig.inputs.iter().filter_map(|arg| {
-        if let syn::FnArg::Typed(arg) = arg {
-            Some(arg)
-        } else {
-            None
-        }
+fn invoke_method(
+    trait_name: &Ident,
+    method: &syn::TraitItemMethod,
+    rename_all: Option<RenameRule>,
+    codec: Codec,
+) -> TokenStream {
+    let method_ident = &method.sig.ident;
+    let has_context = method.sig.inputs.iter().any(|arg| match arg {
+        syn::FnArg::Typed(arg) => is_context(arg),
+        _ => false,
     });
 
     let unpack_args = {
         let name = arg_type_name(trait_name, method);
-        let args = args.clone().map(|syn::PatType { pat, .. }| pat);
+        let args = method_args(method).map(|syn::PatType { pat, .. }| pat);
+        let context = if has_context {
+            Some(quote! {
+                let __ctx = ::dapr::Context::from_request(&request);
+            })
+        } else {
+            None
+        };
+
+        let unpack = if codec.is_serde() {
+            // the arguments may be encoded with any codec advertised in the `type_url`
+            quote! {
+                let __codec = request.get_ref().data.as_ref().map_or(#codec, |data| {
+                    ::dapr::codec::Codec::detect(data, #codec)
+                });
+                let #name { #(#args),* } = if let Some(ref data) = request.get_ref().data {
+                    ::dapr::codec::decode(__codec, data).map_err(|err|
+                        ::dapr::tonic::Status::new(::dapr::tonic::Code::InvalidArgument, err.to_string())
+                    )?
+                } else {
+                    Default::default()
+                };
+            }
+        } else {
+            let args = method_args(method).map(|syn::PatType { pat, ty, .. }| {
+                quote! {
+                    let #pat: #ty = if let Some(ref data) = request.get_ref().data {
+                        ::dapr::codec::decode_message(data).map_err(|err|
+                            ::dapr::tonic::Status::new(::dapr::tonic::Code::InvalidArgument, err.to_string())
+                        )?
+                    } else {
+                        Default::default()
+                    };
+                }
+            });
+
+            quote! {
+                #(#args)*
+            }
+        };
 
         quote! {
-            let #name { #(#args),* } = if let Some(ref data) = request.get_ref().data {
-                ::dapr::json::unpack(data).map_err(|err|
-                    ::dapr::tonic::Status::new(::dapr::tonic::Code::Internal, err.to_string())
-                )?
-            } else {
-                Default::default()
-            };
+            #unpack
+            #context
         }
     };
 
     let call_method = {
-        let names = args.clone().map(|syn::PatType { pat, .. }| pat);
+        let names = method.sig.inputs.iter().filter_map(|arg| match arg {
+            syn::FnArg::Typed(arg) if is_context(arg) => Some(quote! { &__ctx }),
+            syn::FnArg::Typed(syn::PatType { pat, .. }) => Some(quote! { #pat }),
+            _ => None,
+        });
+        let await_res = if is_async(method) {
+            Some(quote! { .await })
+        } else {
+            None
+        };
 
         quote! {
-            let res = service.#method_name(#(#names),*);
+            let res = service.#method_ident(#(#names),*)#await_res;
         }
     };
 
     let pack_res = {
         let name = result_type_name(trait_name, method);
+        let unwrap_res = if method_output(method).and_then(result_output).is_some() {
+            Some(quote! {
+                let res = res.map_err(::dapr::IntoStatus::into_status)?;
+            })
+        } else {
+            None
+        };
+
+        let response = if has_context {
+            quote! { __ctx.into_response }
+        } else {
+            quote! { ::dapr::tonic::Response::new }
+        };
+
+        let encode = if codec.is_serde() {
+            // the result is encoded with the same codec as the arguments
+            quote! { ::dapr::codec::encode(__codec, &#name(res)) }
+        } else {
+            quote! { ::dapr::codec::encode_message(&res) }
+        };
 
         quote! {
-            let res = #name(res);
+            #unwrap_res
+            let res = #encode.map_err(|err| {
+                ::dapr::tonic::Status::new(::dapr::tonic::Code::Internal, err.to_string())
+            })?;
 
-            Ok(::dapr::tonic::Response::new(::dapr::json(&res).unwrap_or_default()))
+            Ok(#response(res))
         }
     };
 
-    let method_pattern = method_name.to_string();
+    let method_pattern = {
+        let name = method_name(method, rename_all);
+        let aliases = method_aliases(method);
+
+        quote! { #name #(| #aliases)* }
+    };
 
     quote! {
         #method_pattern => {
@@ -259,3 +510,143 @@ pub fn result_type_name(trait_name: &Ident, method: &syn::TraitItemMethod) -> Id
         method.sig.ident.to_string().to_camel()
     )
 }
+
+/// The arguments of the method which are serialized, without `self` and the `&Context` parameter.
+pub fn method_args(method: &syn::TraitItemMethod) -> impl Iterator<Item = &syn::PatType> + Clone {
+    method.sig.inputs.iter().filter_map(|arg| match arg {
+        syn::FnArg::Typed(arg) if !is_context(arg) => Some(arg),
+        _ => None,
+    })
+}
+
+/// The argument is the `&Context` parameter, e.g. `ctx: &dapr::Context`.
+pub fn is_context(arg: &syn::PatType) -> bool {
+    match *arg.ty {
+        syn::Type::Reference(syn::TypeReference { ref elem, .. }) => match **elem {
+            syn::Type::Path(syn::TypePath { ref path, .. }) => match path.segments.last() {
+                Some(segment) => segment.ident == "Context",
+                None => false,
+            },
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// The method is an `async fn` or returns a future.
+pub fn is_async(method: &syn::TraitItemMethod) -> bool {
+    method.sig.asyncness.is_some() || returns_future(&method.sig.output).is_some()
+}
+
+/// The type of value returned by the method, or by the future it returns.
+pub fn method_output(method: &syn::TraitItemMethod) -> Option<&syn::Type> {
+    match method.sig.output {
+        syn::ReturnType::Default => None,
+        syn::ReturnType::Type(_, ref ty) => match returns_future(&method.sig.output) {
+            Some(output) => output,
+            None => Some(ty),
+        },
+    }
+}
+
+/// The `Ok` and `Err` types of `Result<T, E>`.
+pub fn result_output(ty: &syn::Type) -> Option<(&syn::Type, &syn::Type)> {
+    match ty {
+        syn::Type::Paren(syn::TypeParen { elem, .. })
+        | syn::Type::Group(syn::TypeGroup { elem, .. }) => result_output(elem),
+        syn::Type::Path(syn::TypePath { path, .. }) => {
+            let segment = path.segments.last()?;
+
+            if segment.ident != "Result" {
+                return None;
+            }
+
+            match segment.arguments {
+                syn::PathArguments::AngleBracketed(ref args) => {
+                    let mut types = args.args.iter().filter_map(|arg| match arg {
+                        syn::GenericArgument::Type(ty) => Some(ty),
+                        _ => None,
+                    });
+
+                    match (types.next(), types.next(), types.next()) {
+                        (Some(ok), Some(err), None) => Some((ok, err)),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// The `Output` type of the future returned by the method, `Some(None)` for `()`.
+///
+/// The future may be `impl Future<Output = T>`, `Pin<Box<dyn Future<Output = T>>>` or `BoxFuture<'a, T>`.
+fn returns_future(output: &syn::ReturnType) -> Option<Option<&syn::Type>> {
+    match output {
+        syn::ReturnType::Default => None,
+        syn::ReturnType::Type(_, ref ty) => future_output(ty),
+    }
+}
+
+fn future_output(ty: &syn::Type) -> Option<Option<&syn::Type>> {
+    match ty {
+        syn::Type::ImplTrait(syn::TypeImplTrait { bounds, .. })
+        | syn::Type::TraitObject(syn::TypeTraitObject { bounds, .. }) => {
+            bounds.iter().find_map(|bound| match bound {
+                syn::TypeParamBound::Trait(syn::TraitBound { path, .. }) => {
+                    let segment = path.segments.last()?;
+
+                    if segment.ident != "Future" {
+                        return None;
+                    }
+
+                    match segment.arguments {
+                        syn::PathArguments::AngleBracketed(ref args) => {
+                            Some(args.args.iter().find_map(|arg| match arg {
+                                syn::GenericArgument::Binding(binding)
+                                    if binding.ident == "Output" && !is_unit(&binding.ty) =>
+                                {
+                                    Some(&binding.ty)
+                                }
+                                _ => None,
+                            }))
+                        }
+                        _ => Some(None),
+                    }
+                }
+                _ => None,
+            })
+        }
+        syn::Type::Paren(syn::TypeParen { elem, .. })
+        | syn::Type::Group(syn::TypeGroup { elem, .. }) => future_output(elem),
+        syn::Type::Path(syn::TypePath { path, .. }) => {
+            let segment = path.segments.last()?;
+            let args = match segment.arguments {
+                syn::PathArguments::AngleBracketed(ref args) => &args.args,
+                _ => return None,
+            };
+            let ty = args.iter().rev().find_map(|arg| match arg {
+                syn::GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            })?;
+
+            if segment.ident == "Pin" || segment.ident == "Box" {
+                future_output(ty)
+            } else if segment.ident == "BoxFuture" || segment.ident == "LocalBoxFuture" {
+                Some(if is_unit(ty) { None } else { Some(ty) })
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+fn is_unit(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Tuple(syn::TypeTuple { elems, .. }) => elems.is_empty(),
+        _ => false,
+    }
+}
@@ -0,0 +1,81 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{parse_quote, ItemTrait, TraitItem};
+
+use crate::args::{strip_attrs, ServiceArgs};
+use crate::client::{dapr_client, method_args, method_output, service_trait};
+use crate::mock::dapr_mock;
+use crate::stub::{async_trait, impl_stub};
+
+pub fn dapr_interface(item: &ItemTrait, args: &ServiceArgs) -> TokenStream {
+    let trait_name = &item.ident;
+
+    let service = service_trait(item);
+    let client = dapr_client(item, args);
+
+    let remote = remote_trait(item);
+    let stub = impl_stub(trait_name, &remote, args.rename_all, args.codec);
+    let mock = if cfg!(feature = "mocking") {
+        Some(dapr_mock(&remote, &format_ident!("{}Mock", trait_name)))
+    } else {
+        None
+    };
+    let mut remote = remote;
+    strip_attrs(&mut remote);
+    let remote = async_trait(remote);
+
+    quote! {
+        #service
+        #client
+        #remote
+        #stub
+        #mock
+    }
+}
+
+/// The client side trait of the service, which is implemented by the stub and mock.
+///
+/// The methods take `&self` and the serialized arguments, and return the output of the service method.
+fn remote_trait(item: &ItemTrait) -> ItemTrait {
+    let items = item
+        .items
+        .iter()
+        .filter_map(|item| {
+            if let TraitItem::Method(method) = item {
+                let args = method_args(method);
+                let output = match method_output(method) {
+                    Some(ty) => parse_quote! { -> #ty },
+                    None => syn::ReturnType::Default,
+                };
+
+                Some(TraitItem::Method(syn::TraitItemMethod {
+                    attrs: method.attrs.clone(),
+                    sig: syn::Signature {
+                        asyncness: None,
+                        inputs: parse_quote! { &self, #(#args),* },
+                        output,
+                        ..method.sig.clone()
+                    },
+                    default: None,
+                    semi_token: Some(Default::default()),
+                }))
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    ItemTrait {
+        attrs: item
+            .attrs
+            .iter()
+            .filter(|attr| attr.path.is_ident("doc"))
+            .cloned()
+            .collect(),
+        ident: format_ident!("{}Remote", item.ident),
+        colon_token: None,
+        supertraits: Default::default(),
+        items,
+        ..item.clone()
+    }
+}
@@ -1,20 +1,56 @@
 extern crate proc_macro;
 
 use proc_macro::TokenStream;
-use quote::quote;
-use syn::parse_macro_input;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, DeriveInput};
 
+mod any;
+mod args;
 mod client;
+mod interface;
 mod mock;
 mod stub;
 
 /// Implements server side interface for the Dapr service
+///
+/// The service handles the topic and binding events when it opts into them with
+/// `#[dapr::service(events, bindings)]` or the `Events` and `Bindings` supertraits.
+///
+/// The methods may be `async fn`, which should be implemented with `#[dapr::async_trait]`,
+/// or return a `Send` future.
+///
+/// The error of methods which return `Result<T, E>` is returned as gRPC status with `dapr::IntoStatus`.
+///
+/// The methods may declare a `ctx: &dapr::Context` parameter to access the context of invocation.
+///
+/// The methods are invoked with their names, which may be changed with `#[dapr(name = "...")]` on the method
+/// or `#[dapr::service(rename_all = "camelCase")]` on the trait, and the old names are accepted with
+/// `#[dapr(alias = "...")]`.
+///
+/// The arguments and results are encoded as JSON by default, or with `#[dapr::service(codec = "...")]`
+/// as `msgpack` or `cbor`, which fail to compile unless the feature of `dapr` is enabled. The service
+/// accepts the arguments in any codec advertised in the `type_url`, and responds in the same codec.
+/// With the `protobuf` codec, the methods take at most one Protobuf message, which is passed without
+/// the arguments wrapper.
+///
+/// The JSON schemas of the arguments and results are described with `#[dapr::service(schema)]`
+/// and the `schema` feature of `dapr`, which implements `dapr::schema::DescribeService` for the client.
+/// The arguments and results must implement `schemars::JsonSchema`.
+///
+/// Several services may be served from one app with `dapr::Router`, which dispatches the methods
+/// by the names listed in `dapr::router::ServiceMethods`.
 #[proc_macro_attribute]
-pub fn service(_args: TokenStream, input: TokenStream) -> TokenStream {
+pub fn service(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(args as syn::AttributeArgs);
     let item = parse_macro_input!(input as syn::ItemTrait);
-    let client = client::dapr_client(&item);
+    let args = match args::ServiceArgs::parse(args, &item) {
+        Ok(args) => args,
+        Err(err) => return TokenStream::from(err.to_compile_error()),
+    };
+    let client = client::dapr_client(&item, &args);
+    let service = client::service_trait(&item);
     let expanded = quote! {
-        #item
+        #service
         #client
     };
     if std::env::var_os("DUMP_SERVICE").is_some() {
@@ -24,15 +60,31 @@ pub fn service(_args: TokenStream, input: TokenStream) -> TokenStream {
 }
 
 /// Implements client site stub for the Dapr service
+///
+/// The gRPC status of methods which return `Result<T, E>` is turned back into `E` with `dapr::FromStatus`.
+///
+/// The methods are renamed with `#[dapr(name = "...")]` and `#[dapr::stub(rename_all = "...")]`
+/// like `#[dapr::service]`.
+///
+/// The arguments are encoded with `#[dapr::stub(codec = "...")]` like `#[dapr::service]`.
+///
+/// The stub owns a `Runtime`, and may be cloned and shared when the methods take `&self`.
 #[proc_macro_attribute]
-pub fn stub(_args: TokenStream, input: TokenStream) -> TokenStream {
+pub fn stub(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(args as syn::AttributeArgs);
     let item = parse_macro_input!(input as syn::ItemTrait);
-    let stub = stub::dapr_stub(&item);
+    let args = match args::StubArgs::parse(args, &item) {
+        Ok(args) => args,
+        Err(err) => return TokenStream::from(err.to_compile_error()),
+    };
+    let stub = stub::dapr_stub(&item, &args);
     let mock = if cfg!(feature = "mocking") {
-        Some(mock::dapr_mock(&item))
+        Some(mock::dapr_mock(&item, &format_ident!("{}Mock", item.ident)))
     } else {
         None
     };
+    let mut item = item;
+    args::strip_attrs(&mut item);
     let async_trait = stub::async_trait(item);
 
     let expanded = quote! {
@@ -46,12 +98,68 @@ pub fn stub(_args: TokenStream, input: TokenStream) -> TokenStream {
     TokenStream::from(expanded)
 }
 
+/// Implements the server side interface, the client side stub and the mock from one trait
+///
+/// The trait is declared like `#[dapr::service]` and accepts the same arguments, the stub and mock
+/// implement the generated `{Trait}Remote` trait, whose async methods take `&self` and return `Result`.
+#[proc_macro_attribute]
+pub fn interface(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(args as syn::AttributeArgs);
+    let item = parse_macro_input!(input as syn::ItemTrait);
+    let args = match args::ServiceArgs::parse(args, &item) {
+        Ok(args) => args,
+        Err(err) => return TokenStream::from(err.to_compile_error()),
+    };
+    let expanded = interface::dapr_interface(&item, &args);
+    if std::env::var_os("DUMP_INTERFACE").is_some() {
+        println!("{}", rustfmt(expanded.to_string()));
+    }
+    TokenStream::from(expanded)
+}
+
+/// Implements `dapr::any::IntoAny` for the type
+///
+/// The value is encoded as JSON by default, or with `#[dapr(codec = "...")]` as `protobuf`,
+/// `msgpack` or `cbor`. The `type_url` names the type with its module path, e.g. `my_app::shop::Order`,
+/// which may be changed with `#[dapr(name = "...")]` to keep it stable when the type is moved or
+/// renamed. The generic types must be named, and their instances share the name.
+///
+/// The `protobuf` types are named `type.googleapis.com/{name}` when packed with
+/// `dapr::any::Encoding::WellKnown`, e.g. as `dapr::any::WellKnown(order)`, where the name should
+/// be the full name of the Protobuf message, e.g. `shop.Order`.
+#[proc_macro_derive(IntoAny, attributes(dapr))]
+pub fn derive_into_any(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let attrs = match args::TypeAttrs::parse(&input) {
+        Ok(attrs) => attrs,
+        Err(err) => return TokenStream::from(err.to_compile_error()),
+    };
+
+    TokenStream::from(any::into_any(&input, &attrs))
+}
+
+/// Implements `dapr::any::TryFromAny` for the type
+///
+/// The type is declared with the same `#[dapr(codec = "...", name = "...")]` as `IntoAny`.
+/// The `type_url` must name the type, and the value is decoded with the codec it advertises.
+/// `try_from_lenient` skips the check and falls back to the declared codec.
+#[proc_macro_derive(TryFromAny, attributes(dapr))]
+pub fn derive_try_from_any(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let attrs = match args::TypeAttrs::parse(&input) {
+        Ok(attrs) => attrs,
+        Err(err) => return TokenStream::from(err.to_compile_error()),
+    };
+
+    TokenStream::from(any::try_from_any(&input, &attrs))
+}
+
 fn rustfmt(input: String) -> String {
     use std::io::Write;
     use std::process::{Command, Stdio};
 
     let mut child = Command::new("rustfmt")
-        .args(&["--emit", "stdout", "--color", "auto", "--edition", "2018"])
+        .args(["--emit", "stdout", "--color", "auto", "--edition", "2018"])
         .stdin(Stdio::piped())
         .stdout(Stdio::piped())
         .spawn()
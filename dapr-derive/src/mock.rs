@@ -1,12 +1,12 @@
 use proc_macro2::TokenStream;
 use quote::{format_ident, quote};
-use syn::TraitItem;
+use syn::{Ident, TraitItem};
 
 use crate::stub::output_type;
 
-pub fn dapr_mock(item: &syn::ItemTrait) -> TokenStream {
-    let mock_struct = create_mock_struct(item);
-    let mock_trait = impl_mock_trait(item);
+pub fn dapr_mock(item: &syn::ItemTrait, mock_name: &Ident) -> TokenStream {
+    let mock_struct = create_mock_struct(item, mock_name);
+    let mock_trait = impl_mock_trait(item, mock_name);
 
     quote! {
         #mock_struct
@@ -14,36 +14,40 @@ pub fn dapr_mock(item: &syn::ItemTrait) -> TokenStream {
     }
 }
 
-fn create_mock_struct(item: &syn::ItemTrait) -> TokenStream {
-    let syn::ItemTrait { ident, items, .. } = item;
-    let trait_name = &ident;
-    let mock_name = format_ident!("{}Mock", trait_name);
+fn create_mock_struct(item: &syn::ItemTrait, mock_name: &Ident) -> TokenStream {
+    let syn::ItemTrait { items, .. } = item;
+    let expectations_name = expectations_name(mock_name);
 
-    let methods = items.iter().filter_map(|item| {
-        if let TraitItem::Method(method) = item {
-            Some(method)
-        } else {
-            None
-        }
-    });
-
-    let expect_methods = methods.map(|method| {
-        let method_name = method.sig.ident.to_string();
-        let expect_method = format_ident!("expect_{}", method_name);
-        let args = method.sig.inputs.iter().flat_map(|input| {
-            if let syn::FnArg::Typed(syn::PatType { ty, .. }) = input {
-                Some(ty)
+    let methods = items
+        .iter()
+        .filter_map(|item| {
+            if let TraitItem::Method(method) = item {
+                Some(method)
             } else {
                 None
             }
-        });
-        let output = match output_type(&method.sig.output) {
-            syn::ReturnType::Type(_, ty) => Some(quote! { => #ty }),
-            syn::ReturnType::Default => None,
-        };
+        })
+        .collect::<Vec<_>>();
+
+    let create_expect_methods = methods.iter().map(|method| {
+        let method_name = method.sig.ident.to_string();
+        let expect_method = format_ident!("expect_{}", method_name);
+        let inputs = method_inputs(method);
+        let output = method_output(method);
 
         quote! {
-            #expect_method(#method_name) #(#args),* #output;
+            #expect_method(#method_name) #inputs => #output;
+        }
+    });
+    let expect_methods = methods.iter().map(|method| {
+        let expect_method = format_ident!("expect_{}", method.sig.ident);
+        let inputs = method_inputs(method);
+        let output = method_output(method);
+
+        quote! {
+            pub fn #expect_method(&mut self) -> ::dapr::simulacrum::Method<'_, #inputs, #output> {
+                self.expectations().#expect_method()
+            }
         }
     });
 
@@ -51,23 +55,84 @@ fn create_mock_struct(item: &syn::ItemTrait) -> TokenStream {
         use ::dapr::simulacrum::*;
 
         create_mock_struct! {
-            struct #mock_name : {
-                #(#expect_methods)*
+            struct #expectations_name : {
+                #(#create_expect_methods)*
             }
         }
 
-        unsafe impl Send for #mock_name {}
+        // SAFETY: the expectations are only shared behind the `Mutex` of the mock, which
+        // serializes the calls to the `returning` closures and validators, the same as the
+        // `Mutex` which `simulacrum` keeps the expectations in.
+        unsafe impl Send for #expectations_name {}
+
+        /// The mock is `Send` and `Sync`, so it may be shared with the handlers like the stub.
+        pub struct #mock_name(::std::sync::Mutex<#expectations_name>);
+
+        impl #mock_name {
+            pub fn new() -> Self {
+                #mock_name(::std::sync::Mutex::new(#expectations_name::new()))
+            }
+
+            pub fn then(&mut self) -> &mut Self {
+                self.expectations().then();
+                self
+            }
+
+            fn expectations(&mut self) -> &mut #expectations_name {
+                self.0.get_mut().unwrap_or_else(|err| err.into_inner())
+            }
+
+            #(#expect_methods)*
+        }
+
+        impl Default for #mock_name {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
     }
 }
 
-fn impl_mock_trait(item: &syn::ItemTrait) -> TokenStream {
+/// The expectations of the mock, which are generated by `simulacrum`.
+fn expectations_name(mock_name: &Ident) -> Ident {
+    format_ident!("{}Expectations", mock_name)
+}
+
+/// The type of arguments, which `simulacrum` passes as a tuple if there is more than one.
+fn method_inputs(method: &syn::TraitItemMethod) -> TokenStream {
+    let args = method
+        .sig
+        .inputs
+        .iter()
+        .flat_map(|input| {
+            if let syn::FnArg::Typed(syn::PatType { ty, .. }) = input {
+                Some(ty)
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+
+    match args.as_slice() {
+        [ty] => quote! { #ty },
+        args => quote! { ( #(#args),* ) },
+    }
+}
+
+fn method_output(method: &syn::TraitItemMethod) -> TokenStream {
+    match output_type(&method.sig.output) {
+        syn::ReturnType::Type(_, ty) => quote! { #ty },
+        syn::ReturnType::Default => quote! { () },
+    }
+}
+
+fn impl_mock_trait(item: &syn::ItemTrait, mock_name: &Ident) -> TokenStream {
     let syn::ItemTrait {
         ident,
         generics,
         ref items,
         ..
     } = item;
-    let mock_name = format_ident!("{}Mock", ident);
     let (impl_generics, ty_generics, _where_clause) = generics.split_for_impl();
 
     let items = items.iter().map(|item| {
@@ -92,7 +157,9 @@ fn impl_mock_trait(item: &syn::ItemTrait) -> TokenStream {
 
             quote! {
                 async #method_sig {
-                    was_called!(self, #method_name, ( #(#args),* ) #output)
+                    let expectations = self.0.lock().unwrap_or_else(|err| err.into_inner());
+
+                    was_called!(expectations, #method_name, ( #(#args),* ) #output)
                 }
             }
         } else {
@@ -2,12 +2,14 @@ use proc_macro2::TokenStream;
 use quote::{format_ident, quote};
 use syn::{parse_quote, Ident, TraitItem};
 
-use crate::client::{arg_type_name, arg_types, result_type_name, result_types};
+use crate::args::{method_name, Codec, RenameRule, StubArgs};
+use crate::client::{
+    arg_type_name, arg_types, method_args, result_output, result_type_name, result_types,
+};
 
-pub fn dapr_stub(item: &syn::ItemTrait) -> TokenStream {
+pub fn dapr_stub(item: &syn::ItemTrait, args: &StubArgs) -> TokenStream {
     let syn::ItemTrait { ident, items, .. } = item;
     let trait_name = &ident;
-    let stub_name = format_ident!("{}Stub", trait_name);
 
     let methods = items.iter().filter_map(|item| {
         if let TraitItem::Method(method) = item {
@@ -17,84 +19,169 @@ pub fn dapr_stub(item: &syn::ItemTrait) -> TokenStream {
         }
     });
 
-    let arg_types = arg_types(trait_name, methods.clone());
-    let result_types = result_types(trait_name, methods.clone());
-    let invoke_methods = methods.map(|method| invoke_method(trait_name, method));
+    let arg_types = arg_types(trait_name, methods.clone(), args.codec, false);
+    let result_types = result_types(trait_name, methods, args.codec, false);
+    let stub = impl_stub(trait_name, item, args.rename_all, args.codec);
 
     quote! {
-        pub struct #stub_name<'a, T> {
-            runtime: &'a mut ::dapr::runtime::Runtime<T>,
+        #arg_types
+        #result_types
+        #stub
+    }
+}
+
+/// Implements the stub of `trait_name` for the `item` trait, which shares the argument and result types.
+pub fn impl_stub(
+    trait_name: &Ident,
+    item: &syn::ItemTrait,
+    rename_all: Option<RenameRule>,
+    codec: Codec,
+) -> TokenStream {
+    let stub_name = format_ident!("{}Stub", trait_name);
+    let impl_trait = &item.ident;
+
+    let invoke_methods = item.items.iter().filter_map(|item| {
+        if let TraitItem::Method(method) = item {
+            Some(invoke_method(trait_name, method, rename_all, codec))
+        } else {
+            None
+        }
+    });
+
+    // the methods with `&self` receiver invoke the service with a clone of runtime
+    let clone_bound = if item.items.iter().any(|item| match item {
+        TraitItem::Method(method) => is_shared_receiver(method),
+        _ => false,
+    }) {
+        Some(quote! { T: Clone, })
+    } else {
+        None
+    };
+
+    quote! {
+        #[derive(Clone)]
+        pub struct #stub_name<T> {
+            runtime: ::dapr::runtime::Runtime<T>,
             app_id: String,
         }
 
-        impl<'a, T> #stub_name<'a, T> {
-            pub fn new<S: Into<String>>(runtime: &'a mut ::dapr::runtime::Runtime<T>, app_id: S) -> Self {
+        impl<T> #stub_name<T> {
+            pub fn new<S: Into<String>>(runtime: ::dapr::runtime::Runtime<T>, app_id: S) -> Self {
                 #stub_name { runtime, app_id: app_id.into() }
             }
-        }
 
-        #arg_types
-        #result_types
+            pub fn app_id(&self) -> &str {
+                &self.app_id
+            }
+
+            pub fn runtime(&self) -> &::dapr::runtime::Runtime<T> {
+                &self.runtime
+            }
+
+            pub fn into_inner(self) -> ::dapr::runtime::Runtime<T> {
+                self.runtime
+            }
+        }
 
         #[::dapr::async_trait]
-        impl<'a, T> #trait_name for #stub_name<'a, T>
+        impl<T> #impl_trait for #stub_name<T>
         where
-            T: ::dapr::tonic::client::GrpcService<tonic::body::BoxBody> + Send + Sync,
+            T: ::dapr::tonic::client::GrpcService<::dapr::tonic::body::BoxBody> + Send + Sync,
             T::Future: Send,
             T::ResponseBody: ::dapr::tonic::codegen::Body + ::dapr::tonic::codegen::HttpBody + Send + 'static,
             T::Error: Into<::dapr::tonic::codegen::StdError>,
             <T::ResponseBody as ::dapr::tonic::codegen::HttpBody>::Error: Into<::dapr::tonic::codegen::StdError> + Send,
             <T::ResponseBody as ::dapr::tonic::codegen::HttpBody>::Data: Into<::dapr::bytes::Bytes> + Send,
+            #clone_bound
         {
             #(#invoke_methods)*
         }
     }
 }
 
-pub fn invoke_method(trait_name: &Ident, method: &syn::TraitItemMethod) -> TokenStream {
-    let method_name = method.sig.ident.to_string();
+pub fn invoke_method(
+    trait_name: &Ident,
+    method: &syn::TraitItemMethod,
+    rename_all: Option<RenameRule>,
+    codec: Codec,
+) -> TokenStream {
+    let method_name = method_name(method, rename_all);
     let method_output = output_type(&method.sig.output);
     let method_sig = syn::Signature {
         output: method_output,
         ..method.sig.clone()
     };
-    let args = method.sig.inputs.iter().filter_map(|arg| {
-        if let syn::FnArg::Typed(arg) = arg {
-            Some(arg)
-        } else {
-            None
-        }
-    });
-
-    let pack_args = {
+    let pack_args = if codec.is_serde() {
         let name = arg_type_name(trait_name, method);
-        let args = args.clone().map(|syn::PatType { pat, .. }| pat);
+        let args = method_args(method).map(|syn::PatType { pat, .. }| pat);
 
         quote! {
             let args = #name { #(#args),* };
-            let args = ::dapr::json::pack(&args)?;
+            let args = ::dapr::codec::encode(#codec, &args)?;
+        }
+    } else {
+        let arg = method_args(method)
+            .map(|syn::PatType { pat, .. }| quote! { #pat })
+            .next()
+            .unwrap_or_else(|| quote! { () });
+
+        quote! {
+            let args = ::dapr::codec::encode_message(&#arg)?;
         }
     };
 
-    let invoke_service = quote! {
-        let (res, _metadata) = self.runtime.invoke_service(&self.app_id, #method_name, args).await?;
+    let invoke_service = if is_shared_receiver(method) {
+        quote! {
+            let mut runtime = self.runtime.clone();
+            let (res, _metadata) = runtime.invoke_service(&self.app_id, #method_name, args).await?;
+        }
+    } else {
+        quote! {
+            let (res, _metadata) = self.runtime.invoke_service(&self.app_id, #method_name, args).await?;
+        }
     };
 
-    let unpack_res = {
+    let unpack_res = if codec.is_serde() {
         let name = result_type_name(trait_name, method);
 
+        // the service responds with the codec advertised in the `type_url`
+        quote! {
+            let #name(res) = match res {
+                Some(ref res) => ::dapr::codec::decode(::dapr::codec::Codec::detect(res, #codec), res)?,
+                None => Default::default(),
+            };
+            Ok(res)
+        }
+    } else {
         quote! {
-            let #name(res) = res.as_ref().map(::dapr::json::unpack).transpose()?.unwrap_or_default();
+            let res = match res {
+                Some(ref res) => ::dapr::codec::decode_message(res)?,
+                None => Default::default(),
+            };
             Ok(res)
         }
     };
 
-    quote! {
-        async #method_sig {
-            #pack_args
-            #invoke_service
-            #unpack_res
-        }
+    match fallible_output(&method.sig.output) {
+        // the errors are turned into gRPC status, then back into the typed errors
+        Some((ok, _)) => quote! {
+            async #method_sig {
+                let res: ::core::result::Result<#ok, ::dapr::Error> = async {
+                    #pack_args
+                    #invoke_service
+                    #unpack_res
+                }.await;
+
+                res.map_err(|err| ::dapr::FromStatus::from_status(::dapr::IntoStatus::into_status(err)))
+            }
+        },
+        None => quote! {
+            async #method_sig {
+                #pack_args
+                #invoke_service
+                #unpack_res
+            }
+        },
     }
 }
 
@@ -129,7 +216,28 @@ pub fn async_trait(item: syn::ItemTrait) -> TokenStream {
     }
 }
 
+/// The method takes `&self` instead of `&mut self`.
+fn is_shared_receiver(method: &syn::TraitItemMethod) -> bool {
+    match method.sig.inputs.first() {
+        Some(syn::FnArg::Receiver(receiver)) => receiver.mutability.is_none(),
+        _ => false,
+    }
+}
+
+/// The `Ok` and `Err` types of the method which returns `Result<T, E>`.
+fn fallible_output(output: &syn::ReturnType) -> Option<(&syn::Type, &syn::Type)> {
+    match output {
+        syn::ReturnType::Default => None,
+        syn::ReturnType::Type(_, ref ty) => result_output(ty),
+    }
+}
+
+/// The return type of the stub method, the `Result<T, E>` returned as-is.
 pub fn output_type(output: &syn::ReturnType) -> syn::ReturnType {
+    if fallible_output(output).is_some() {
+        return output.clone();
+    }
+
     match output {
         syn::ReturnType::Default => {
             parse_quote! {
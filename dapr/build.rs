@@ -1,4 +1,5 @@
 fn main() {
     tonic_build::compile_protos("proto/dapr.proto").unwrap();
     tonic_build::compile_protos("proto/daprclient.proto").unwrap();
+    tonic_build::compile_protos("proto/health.proto").unwrap();
 }
@@ -0,0 +1,8 @@
+//! The interface shared by the client and the caller.
+
+#![allow(dead_code)]
+
+#[dapr::interface]
+pub trait MyService {
+    fn my_method(&self, name: String) -> String;
+}
@@ -1,9 +1,7 @@
-use dapr::Unpack;
+mod api;
 
-#[dapr::stub]
-pub trait MyService {
-    fn my_method(&mut self, name: String) -> String;
-}
+use api::{MyServiceRemote, MyServiceStub};
+use dapr::Unpack;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -14,11 +12,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Create the client
     let mut client = dapr::connect(addr)?;
 
-    let res = {
-        let mut stub = MyServiceStub::new(&mut client, "client");
+    // Create the stub, which may be cloned and shared with the handlers
+    let stub = MyServiceStub::new(client.clone(), "client");
 
-        stub.my_method("world".to_owned()).await?
-    };
+    let res = stub.my_method("world".to_owned()).await?;
 
     println!("{:?}", res);
 
@@ -32,10 +29,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     println!("Saved state!");
 
-    // Get state for key myKey
+    // Get state for key myKey, the state store keeps the bytes without the `type_url`
     let (res, _) = client.get_state("myKey").await?;
 
-    println!("Got state: {}", res.unwrap().unpack::<String>()?);
+    println!("Got state: {}", res.unwrap().unpack_lenient::<String>()?);
 
     // Delete state for key myKey
     client.delete_state("myKey").await?;
@@ -1,12 +1,11 @@
-use dapr::client::server::DaprClientServer;
+mod api;
 
-/// MyServer is our user app
-pub struct MyServer {}
+use api::{MyService, MyServiceClient};
+use dapr::client::{BindingEventEnvelope, BindingResponse, BindingRouter, EventRouter};
+use dapr::Router;
 
-#[dapr::service]
-pub trait MyService: dapr::client::Events + dapr::client::Bindings {
-    fn my_method(&self, name: String) -> String;
-}
+/// MyServer is our user app
+pub struct MyServer;
 
 impl MyService for MyServer {
     /// Sample method to invoke
@@ -15,51 +14,26 @@ impl MyService for MyServer {
     }
 }
 
-#[tonic::async_trait]
-impl dapr::client::Events for MyServer {
-    type Error = tonic::Status;
-
-    async fn topic_subscriptions(&self) -> Result<Vec<String>, Self::Error> {
-        Ok(vec!["TopicA".to_owned()])
-    }
-
-    async fn on_topic_event(
-        &self,
-        event: dapr::client::CloudEventEnvelope,
-    ) -> Result<(), Self::Error> {
-        println!("Topic message arrived: {:?}", event);
-
-        Ok(())
-    }
-}
-
-#[tonic::async_trait]
-impl dapr::client::Bindings for MyServer {
-    type Error = tonic::Status;
-
-    async fn bindings_subscriptions(&self) -> Result<Vec<String>, Self::Error> {
-        Ok(vec!["TopicA".to_owned()])
-    }
-
-    async fn on_binding_event(
-        &self,
-        event: dapr::client::BindingEventEnvelope,
-    ) -> Result<dapr::client::BindingResponseEnvelope, Self::Error> {
-        println!("Invoked from binding: {:?}", event);
-
-        Ok(Default::default())
-    }
-}
-
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let addr = "127.0.0.1:4000".parse().unwrap();
-    let server = MyServiceClient(MyServer {});
+    let events = EventRouter::new().event("TopicA", |event: dapr::CloudEvent| async move {
+        println!("Topic message arrived: {:?}", event);
 
-    // create grpc server
-    tonic::transport::Server::builder()
-        .serve(addr, DaprClientServer::new(server))
-        .await?;
+        Ok(())
+    });
+    let bindings =
+        BindingRouter::new().binding("TopicA", |event: BindingEventEnvelope| async move {
+            println!("Invoked from binding: {:?}", event);
+
+            Ok(BindingResponse::new())
+        });
+    let router = Router::new()
+        .service(MyServiceClient::new(MyServer))?
+        .events(events)
+        .bindings(bindings);
+
+    // serve on `DAPR_APP_PORT` until SIGTERM
+    dapr::App::new(router).serve_from_env().await?;
 
     Ok(())
 }
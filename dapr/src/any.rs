@@ -1,106 +1,435 @@
 //! Convert between `Any` type and primitives types.
+//!
+//! The numbers are encoded in a portable format, whose version is recorded in the `type_url`,
+//! e.g. `rust-lang.org/v1/u32`. The `v1` format is fixed-width little-endian, with `usize` and
+//! `isize` widened to 64 bits and `char` written as `u32`, so the values may be exchanged between
+//! platforms. The byte buffers, `Vec<u8>`, `&[u8]` and `Bytes`, are written as-is as `rust-lang.org/bytes`.
+//!
+//! The legacy values without a version, e.g. `rust-lang.org/u32`, were written in native
+//! byte order and are still accepted. The values without a Rust `type_url`, e.g. read back from a
+//! state store which drops it, are taken as the `v1` format.
+//!
+//! Unpacking checks the type name in the `type_url`, so a `u32` is not unpacked as an `i32`.
+//! The values of foreign producers, which don't record the Rust type, may be unpacked with
+//! `Unpack::unpack_lenient` instead.
+//!
+//! All the conversions fail with `any::Error`, and the encode failures are reported by the
+//! fallible `try_*` counterparts, e.g. `IntoAny::try_into_any`, `try_json` and `try_protobuf`.
+//!
+//! The collections of the supported types, e.g. `Vec<String>` or `HashMap<String, i64>`, and the
+//! tuples are packed in a self-describing format, see `IntoElement` for the elements of `Vec<T>`.
+//!
+//! The values of several types may be decoded by their `type_url` with `AnyRegistry`.
+//!
+//! The Dapr SDKs of other languages exchange the well-known wrapper types of Protobuf instead,
+//! e.g. `type.googleapis.com/google.protobuf.StringValue`. They are always unpacked as the wrapped
+//! type, and the primitive values are packed as them with `Encoding::WellKnown`, either by
+//! `IntoAny::try_into_any_with` or by wrapping the value in `WellKnown`.
 
 use std::any::type_name;
-use std::convert::TryInto;
+use std::convert::{TryFrom, TryInto};
+use std::mem::size_of;
 
+use bytes::Bytes;
 use prost_types::Any;
+use thiserror::Error;
+
+mod collections;
+mod registry;
+pub mod wkt;
+
+pub use collections::{FromElement, IntoElement};
+pub use registry::{AnyRegistry, BoxAny};
 
 const RUST_LANG_URL: &str = "rust-lang.org";
 
-/// Pack the given data as a byte array in native byte order.
+/// The version of the portable encoding.
+const PORTABLE_VERSION: &str = "v1";
+
+/// Any error
+#[derive(Error, Debug)]
+pub enum Error {
+    /// The length of value doesn't match the type.
+    #[error("invalid length of {actual} bytes, expected {expected}")]
+    InvalidLength { expected: usize, actual: usize },
+
+    /// The value is out of the range of the type on this platform.
+    #[error("value out of range of `{0}`")]
+    OutOfRange(&'static str),
+
+    /// The `type_url` doesn't match the type.
+    #[error("type mismatch, expected `{expected}`, found `{actual}`")]
+    TypeMismatch {
+        expected: &'static str,
+        actual: String,
+    },
+
+    /// UTF-8 error
+    #[error("UTF-8 error")]
+    Utf8(#[from] std::string::FromUtf8Error),
+
+    /// Protobuf decode error
+    #[error("decode error")]
+    Decode(#[from] prost::DecodeError),
+
+    /// Protobuf encode error
+    #[error("encode error")]
+    Encode(#[from] prost::EncodeError),
+
+    /// JSON error
+    #[cfg(feature = "json")]
+    #[error("JSON error")]
+    Json(#[from] serde_json::error::Error),
+
+    /// MessagePack encode error
+    #[cfg(feature = "msgpack")]
+    #[error("MessagePack encode error")]
+    MsgPackEncode(#[from] rmp_serde::encode::Error),
+
+    /// MessagePack decode error
+    #[cfg(feature = "msgpack")]
+    #[error("MessagePack decode error")]
+    MsgPackDecode(#[from] rmp_serde::decode::Error),
+
+    /// MessagePack value error
+    #[cfg(feature = "msgpack")]
+    #[error("MessagePack value error")]
+    MsgPackValue(#[from] rmpv::ext::Error),
+
+    /// CBOR error
+    #[cfg(feature = "cbor")]
+    #[error("CBOR error")]
+    Cbor(#[from] serde_cbor::error::Error),
+
+    /// The codec is not supported or not enabled.
+    #[error("unsupported codec `{0}`")]
+    UnsupportedCodec(String),
+
+    /// The elements of collection or tuple are malformed.
+    #[error("malformed `{0}`")]
+    Malformed(&'static str),
+
+    /// The `type_url` is not registered.
+    #[error("unknown type `{0}`")]
+    UnknownType(String),
+}
+
+impl From<std::convert::Infallible> for Error {
+    fn from(err: std::convert::Infallible) -> Self {
+        match err {}
+    }
+}
+
+/// The encoding of the primitive values, see `IntoAny::try_into_any_with`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Encoding {
+    /// The Rust types, e.g. `rust-lang.org/v1/u32`.
+    Rust,
+    /// The well-known wrapper types of Protobuf, e.g. `type.googleapis.com/google.protobuf.UInt32Value`.
+    ///
+    /// The values without a wrapper type, e.g. `u128`, are still encoded as the Rust types.
+    WellKnown,
+}
+
+/// The `type_url` of the portable encoding.
+fn portable_type_url(ty: &str) -> String {
+    format!("{}/{}/{}", RUST_LANG_URL, PORTABLE_VERSION, ty)
+}
+
+/// The value is encoded in the legacy native format, whose `type_url` has no version,
+/// e.g. `rust-lang.org/u32`.
+fn is_legacy(type_url: &str) -> bool {
+    type_url
+        .strip_prefix(RUST_LANG_URL)
+        .and_then(|path| path.strip_prefix('/'))
+        .is_some_and(|name| !name.is_empty() && !name.contains('/'))
+}
+
+/// Check the type name in the `type_url` is one of the expected names.
+fn check_type_url(type_url: &str, expected: &[&'static str]) -> Result<(), Error> {
+    let name = type_url
+        .strip_prefix(RUST_LANG_URL)
+        .and_then(|path| path.strip_prefix('/'))
+        .map(|path| {
+            path.strip_prefix(PORTABLE_VERSION)
+                .and_then(|name| name.strip_prefix('/'))
+                .unwrap_or(path)
+        });
+
+    match name {
+        Some(name) if expected.contains(&name) => Ok(()),
+        _ => Err(Error::TypeMismatch {
+            expected: expected[0],
+            actual: type_url.to_owned(),
+        }),
+    }
+}
+
+/// The bytes of fixed-width value.
+fn fixed_bytes<'a, T>(value: &'a [u8]) -> Result<T, Error>
+where
+    T: TryFrom<&'a [u8]>,
+{
+    value.try_into().map_err(|_| Error::InvalidLength {
+        expected: size_of::<T>(),
+        actual: value.len(),
+    })
+}
+
+/// Pack the given data as a byte array, `rust-lang.org/bytes` like `Vec<u8>`.
 pub fn pack<T>(value: T) -> Option<Any>
 where
     T: AsRef<[u8]>,
 {
-    Some(Any {
-        value: value.as_ref().to_vec(),
-        type_url: format!("{}/{}", RUST_LANG_URL, type_name::<T>()),
-    })
+    value.as_ref().into_any()
 }
 
 /// A value-to-value conversion that consumes the input value.
-pub trait IntoAny {
-    /// Performs the conversion.
-    fn into_any(self) -> Option<Any>;
+pub trait IntoAny: Sized {
+    /// Performs the conversion, `None` if there is no data or the encoding failed.
+    fn into_any(self) -> Option<Any> {
+        self.try_into_any().ok().flatten()
+    }
+
+    /// Performs the conversion, `None` if there is no data.
+    fn try_into_any(self) -> Result<Option<Any>, Error>;
+
+    /// Performs the conversion with the encoding, `None` if there is no data.
+    ///
+    /// The types without a well-known representation are converted like `try_into_any`.
+    fn try_into_any_with(self, _encoding: Encoding) -> Result<Option<Any>, Error> {
+        self.try_into_any()
+    }
+}
+
+/// Pack the value as the well-known wrapper types of Protobuf, e.g. to be read by the other Dapr SDKs.
+///
+/// ```
+/// use dapr::any::{wkt::Int64Value, IntoAny, WellKnown};
+///
+/// let any = WellKnown(42i64).into_any().unwrap();
+///
+/// assert_eq!(any.type_url, Int64Value::TYPE_URL);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct WellKnown<T>(pub T);
+
+impl<T> IntoAny for WellKnown<T>
+where
+    T: IntoAny,
+{
+    fn try_into_any(self) -> Result<Option<Any>, Error> {
+        self.0.try_into_any_with(Encoding::WellKnown)
+    }
 }
 
 impl<T> IntoAny for Option<T>
 where
     T: IntoAny,
 {
-    fn into_any(self) -> Option<Any> {
-        self.and_then(|value| value.into_any())
+    fn try_into_any(self) -> Result<Option<Any>, Error> {
+        self.try_into_any_with(Encoding::Rust)
+    }
+
+    fn try_into_any_with(self, encoding: Encoding) -> Result<Option<Any>, Error> {
+        match self {
+            Some(value) => value.try_into_any_with(encoding),
+            None => Ok(None),
+        }
+    }
+}
+
+impl IntoAny for Any {
+    fn try_into_any(self) -> Result<Option<Any>, Error> {
+        Ok(Some(self))
     }
 }
 
 impl IntoAny for bool {
-    fn into_any(self) -> Option<Any> {
-        Some(Any {
+    fn try_into_any(self) -> Result<Option<Any>, Error> {
+        self.try_into_any_with(Encoding::Rust)
+    }
+
+    fn try_into_any_with(self, encoding: Encoding) -> Result<Option<Any>, Error> {
+        if encoding == Encoding::WellKnown {
+            return wkt::BoolValue::from(self).try_into_any();
+        }
+
+        Ok(Some(Any {
             value: vec![if self { 1 } else { 0 }],
             type_url: format!("{}/bool", RUST_LANG_URL),
-        })
+        }))
     }
 }
 
 impl IntoAny for () {
-    fn into_any(self) -> Option<Any> {
-        Some(Any {
+    fn try_into_any(self) -> Result<Option<Any>, Error> {
+        Ok(Some(Any {
             value: vec![],
             type_url: format!("{}/()", RUST_LANG_URL),
-        })
+        }))
     }
 }
 
 macro_rules! impl_into_any {
-    ($ty:ty) => {
+    ($ty:ty $(=> $wkt:ident)?) => {
+        impl_into_any!($ty as $ty $(=> $wkt)?);
+    };
+    ($ty:ty as $repr:ty $(=> $wkt:ident)?) => {
         impl IntoAny for $ty {
-            fn into_any(self) -> Option<Any> {
-                Some(Any {
-                    value: self.to_ne_bytes().to_vec(),
-                    type_url: format!("{}/{}", RUST_LANG_URL, stringify!($ty)),
-                })
+            fn try_into_any(self) -> Result<Option<Any>, Error> {
+                Ok(Some(Any {
+                    value: (self as $repr).to_le_bytes().to_vec(),
+                    type_url: portable_type_url(stringify!($ty)),
+                }))
             }
+
+            $(
+                fn try_into_any_with(self, encoding: Encoding) -> Result<Option<Any>, Error> {
+                    if encoding == Encoding::WellKnown {
+                        return wkt::$wkt { value: self as _ }.try_into_any();
+                    }
+
+                    self.try_into_any()
+                }
+            )?
         }
     };
 }
 
-impl_into_any!(u8);
-impl_into_any!(u16);
-impl_into_any!(u32);
-impl_into_any!(u64);
+impl_into_any!(u8 => UInt32Value);
+impl_into_any!(u16 => UInt32Value);
+impl_into_any!(u32 => UInt32Value);
+impl_into_any!(u64 => UInt64Value);
 impl_into_any!(u128);
-impl_into_any!(usize);
-impl_into_any!(i8);
-impl_into_any!(i16);
-impl_into_any!(i32);
-impl_into_any!(i64);
+impl_into_any!(usize as u64 => UInt64Value);
+impl_into_any!(i8 => Int32Value);
+impl_into_any!(i16 => Int32Value);
+impl_into_any!(i32 => Int32Value);
+impl_into_any!(i64 => Int64Value);
 impl_into_any!(i128);
-impl_into_any!(isize);
+impl_into_any!(isize as i64 => Int64Value);
+impl_into_any!(f32 => FloatValue);
+impl_into_any!(f64 => DoubleValue);
+
+impl IntoAny for char {
+    fn try_into_any(self) -> Result<Option<Any>, Error> {
+        self.try_into_any_with(Encoding::Rust)
+    }
+
+    fn try_into_any_with(self, encoding: Encoding) -> Result<Option<Any>, Error> {
+        if encoding == Encoding::WellKnown {
+            return wkt::StringValue::from(self.to_string()).try_into_any();
+        }
+
+        Ok(Some(Any {
+            value: u32::from(self).to_le_bytes().to_vec(),
+            type_url: portable_type_url("char"),
+        }))
+    }
+}
+
+impl IntoAny for Vec<u8> {
+    fn try_into_any(self) -> Result<Option<Any>, Error> {
+        self.try_into_any_with(Encoding::Rust)
+    }
+
+    fn try_into_any_with(self, encoding: Encoding) -> Result<Option<Any>, Error> {
+        if encoding == Encoding::WellKnown {
+            return wkt::BytesValue::from(self).try_into_any();
+        }
+
+        Ok(Some(Any {
+            value: self,
+            type_url: format!("{}/bytes", RUST_LANG_URL),
+        }))
+    }
+}
+
+impl IntoAny for &[u8] {
+    fn try_into_any(self) -> Result<Option<Any>, Error> {
+        self.to_vec().try_into_any()
+    }
+
+    fn try_into_any_with(self, encoding: Encoding) -> Result<Option<Any>, Error> {
+        self.to_vec().try_into_any_with(encoding)
+    }
+}
+
+/// The buffer is copied once, since `Any::value` is a `Vec<u8>`, which `Bytes` can't give its
+/// storage to, even when the buffer is not shared.
+impl IntoAny for Bytes {
+    fn try_into_any(self) -> Result<Option<Any>, Error> {
+        self.to_vec().try_into_any()
+    }
+
+    fn try_into_any_with(self, encoding: Encoding) -> Result<Option<Any>, Error> {
+        self.to_vec().try_into_any_with(encoding)
+    }
+}
 
 impl IntoAny for &str {
-    fn into_any(self) -> Option<Any> {
-        Some(Any {
+    fn try_into_any(self) -> Result<Option<Any>, Error> {
+        self.try_into_any_with(Encoding::Rust)
+    }
+
+    fn try_into_any_with(self, encoding: Encoding) -> Result<Option<Any>, Error> {
+        if encoding == Encoding::WellKnown {
+            return wkt::StringValue::from(self.to_owned()).try_into_any();
+        }
+
+        Ok(Some(Any {
             value: self.as_bytes().to_vec(),
             type_url: format!("{}/str", RUST_LANG_URL),
-        })
+        }))
     }
 }
 
 impl IntoAny for String {
-    fn into_any(self) -> Option<Any> {
-        Some(Any {
+    fn try_into_any(self) -> Result<Option<Any>, Error> {
+        self.try_into_any_with(Encoding::Rust)
+    }
+
+    fn try_into_any_with(self, encoding: Encoding) -> Result<Option<Any>, Error> {
+        if encoding == Encoding::WellKnown {
+            return wkt::StringValue::from(self).try_into_any();
+        }
+
+        Ok(Some(Any {
             value: self.as_bytes().to_vec(),
             type_url: format!("{}/String", RUST_LANG_URL),
-        })
+        }))
     }
 }
 
-/// Deserialize an instance of type T from a byte array in native byte order.
+/// Deserialize an instance of type T from a byte array.
 pub trait Unpack {
     /// Deserialize an instance of type T.
     fn unpack<T>(self) -> Result<T, T::Error>
     where
         T: TryFromAny;
+
+    /// Deserialize an instance of type T, without checking the `type_url`.
+    fn unpack_lenient<T>(self) -> Result<T, T::Error>
+    where
+        T: TryFromAny;
+}
+
+/// The absent `Any` is unpacked as `Any::default()`, e.g. `None` for `Option<T>`.
+impl Unpack for Option<Any> {
+    fn unpack<T>(self) -> Result<T, T::Error>
+    where
+        T: TryFromAny,
+    {
+        self.unwrap_or_default().unpack()
+    }
+
+    fn unpack_lenient<T>(self) -> Result<T, T::Error>
+    where
+        T: TryFromAny,
+    {
+        self.unwrap_or_default().unpack_lenient()
+    }
 }
 
 impl Unpack for Any {
@@ -110,6 +439,13 @@ impl Unpack for Any {
     {
         T::try_from(self)
     }
+
+    fn unpack_lenient<T>(self) -> Result<T, T::Error>
+    where
+        T: TryFromAny,
+    {
+        T::try_from_lenient(self)
+    }
 }
 
 /// Try convert `Any` to the given type.
@@ -117,53 +453,217 @@ pub trait TryFromAny: Sized {
     /// The type returned in the event of a conversion error.
     type Error;
 
-    /// Performs the conversion.
+    /// Performs the conversion, checking the `type_url`.
     fn try_from(any: Any) -> Result<Self, Self::Error>;
+
+    /// Performs the conversion without checking the `type_url`, e.g. for the values of foreign producers.
+    fn try_from_lenient(any: Any) -> Result<Self, Self::Error> {
+        Self::try_from(any)
+    }
 }
 
 macro_rules! impl_try_from_any {
-    ($ty:ty) => {
+    ($ty:ty $(=> $wkt:ident)?) => {
+        impl_try_from_any!($ty as $ty $(=> $wkt)?);
+    };
+    ($ty:ty as $repr:ty $(=> $wkt:ident)?) => {
         impl TryFromAny for $ty {
-            type Error = std::array::TryFromSliceError;
+            type Error = Error;
 
             fn try_from(any: Any) -> Result<Self, Self::Error> {
-                any.value.as_slice().try_into().map(<$ty>::from_ne_bytes)
+                $(
+                    if any.type_url == wkt::$wkt::TYPE_URL {
+                        return Self::try_from_lenient(any);
+                    }
+                )?
+
+                check_type_url(&any.type_url, &[stringify!($ty)])?;
+
+                Self::try_from_lenient(any)
+            }
+
+            fn try_from_lenient(any: Any) -> Result<Self, Self::Error> {
+                $(
+                    if any.type_url == wkt::$wkt::TYPE_URL {
+                        return wkt::$wkt::try_from_lenient(any)?
+                            .value
+                            .try_into()
+                            .map_err(|_| Error::OutOfRange(stringify!($ty)));
+                    }
+                )?
+
+                if is_legacy(&any.type_url) {
+                    // the legacy values were written in native byte order
+                    fixed_bytes(&any.value).map(<$ty>::from_ne_bytes)
+                } else {
+                    <$repr>::from_le_bytes(fixed_bytes(&any.value)?)
+                        .try_into()
+                        .map_err(|_| Error::OutOfRange(stringify!($ty)))
+                }
             }
         }
     };
 }
 
-impl_try_from_any!(u8);
-impl_try_from_any!(u16);
-impl_try_from_any!(u32);
-impl_try_from_any!(u64);
+impl_try_from_any!(u8 => UInt32Value);
+impl_try_from_any!(u16 => UInt32Value);
+impl_try_from_any!(u32 => UInt32Value);
+impl_try_from_any!(u64 => UInt64Value);
 impl_try_from_any!(u128);
-impl_try_from_any!(usize);
-impl_try_from_any!(i8);
-impl_try_from_any!(i16);
-impl_try_from_any!(i32);
-impl_try_from_any!(i64);
+impl_try_from_any!(usize as u64 => UInt64Value);
+impl_try_from_any!(i8 => Int32Value);
+impl_try_from_any!(i16 => Int32Value);
+impl_try_from_any!(i32 => Int32Value);
+impl_try_from_any!(i64 => Int64Value);
 impl_try_from_any!(i128);
-impl_try_from_any!(isize);
+impl_try_from_any!(isize as i64 => Int64Value);
+impl_try_from_any!(f32 => FloatValue);
+impl_try_from_any!(f64 => DoubleValue);
+
+impl TryFromAny for bool {
+    type Error = Error;
+
+    fn try_from(any: Any) -> Result<Self, Self::Error> {
+        if any.type_url != wkt::BoolValue::TYPE_URL {
+            check_type_url(&any.type_url, &["bool"])?;
+        }
+
+        Self::try_from_lenient(any)
+    }
+
+    fn try_from_lenient(any: Any) -> Result<Self, Self::Error> {
+        if any.type_url == wkt::BoolValue::TYPE_URL {
+            return wkt::BoolValue::try_from_lenient(any).map(|wrapper| wrapper.value);
+        }
+
+        match fixed_bytes(&any.value).map(u8::from_le_bytes)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(Error::OutOfRange("bool")),
+        }
+    }
+}
+
+impl TryFromAny for () {
+    type Error = Error;
+
+    fn try_from(any: Any) -> Result<Self, Self::Error> {
+        check_type_url(&any.type_url, &["()"])?;
+
+        Self::try_from_lenient(any)
+    }
+
+    fn try_from_lenient(any: Any) -> Result<Self, Self::Error> {
+        if any.value.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::InvalidLength {
+                expected: 0,
+                actual: any.value.len(),
+            })
+        }
+    }
+}
+
+impl TryFromAny for char {
+    type Error = Error;
+
+    fn try_from(any: Any) -> Result<Self, Self::Error> {
+        if any.type_url != wkt::StringValue::TYPE_URL {
+            check_type_url(&any.type_url, &["char"])?;
+        }
+
+        Self::try_from_lenient(any)
+    }
+
+    fn try_from_lenient(any: Any) -> Result<Self, Self::Error> {
+        if any.type_url == wkt::StringValue::TYPE_URL {
+            let s = wkt::StringValue::try_from_lenient(any)?.value;
+            let mut chars = s.chars();
+
+            return match (chars.next(), chars.next()) {
+                (Some(c), None) => Ok(c),
+                _ => Err(Error::OutOfRange("char")),
+            };
+        }
+
+        let code = u32::from_le_bytes(fixed_bytes(&any.value)?);
+
+        std::char::from_u32(code).ok_or(Error::OutOfRange("char"))
+    }
+}
+
+impl TryFromAny for Vec<u8> {
+    type Error = Error;
+
+    fn try_from(any: Any) -> Result<Self, Self::Error> {
+        if any.type_url != wkt::BytesValue::TYPE_URL {
+            check_type_url(&any.type_url, &["bytes"])?;
+        }
+
+        Self::try_from_lenient(any)
+    }
+
+    fn try_from_lenient(any: Any) -> Result<Self, Self::Error> {
+        if any.type_url == wkt::BytesValue::TYPE_URL {
+            return wkt::BytesValue::try_from_lenient(any).map(|wrapper| wrapper.value);
+        }
+
+        Ok(any.value)
+    }
+}
+
+/// The buffer of `Any` is taken over without copying.
+impl TryFromAny for Bytes {
+    type Error = Error;
+
+    fn try_from(any: Any) -> Result<Self, Self::Error> {
+        <Vec<u8> as TryFromAny>::try_from(any).map(Bytes::from)
+    }
+
+    fn try_from_lenient(any: Any) -> Result<Self, Self::Error> {
+        Vec::<u8>::try_from_lenient(any).map(Bytes::from)
+    }
+}
 
 impl TryFromAny for String {
-    type Error = std::string::FromUtf8Error;
+    type Error = Error;
 
     fn try_from(any: Any) -> Result<Self, Self::Error> {
-        String::from_utf8(any.value)
+        if any.type_url != wkt::StringValue::TYPE_URL {
+            check_type_url(&any.type_url, &["String", "str"])?;
+        }
+
+        Self::try_from_lenient(any)
+    }
+
+    fn try_from_lenient(any: Any) -> Result<Self, Self::Error> {
+        if any.type_url == wkt::StringValue::TYPE_URL {
+            return wkt::StringValue::try_from_lenient(any).map(|wrapper| wrapper.value);
+        }
+
+        String::from_utf8(any.value).map_err(Error::from)
     }
 }
 
-/// Serialize the given data structure as a Protobuf message.
+/// Serialize the given data structure as a Protobuf message, `None` if the encoding failed.
 pub fn protobuf<T>(value: &T) -> Option<Any>
 where
     T: prost::Message,
 {
-    let mut buf = vec![];
+    try_protobuf(value).ok()
+}
 
-    value.encode(&mut buf).ok()?;
+/// Serialize the given data structure as a Protobuf message.
+pub fn try_protobuf<T>(value: &T) -> Result<Any, Error>
+where
+    T: prost::Message,
+{
+    let mut buf = Vec::with_capacity(value.encoded_len());
+
+    value.encode(&mut buf)?;
 
-    Some(Any {
+    Ok(Any {
         value: buf,
         type_url: format!("{}/{}", RUST_LANG_URL, type_name::<T>()),
     })
@@ -173,8 +673,10 @@ where
 pub mod protobuf {
     use prost_types::Any;
 
+    use super::Error;
+
     /// Deserialize an instance of type T from Protobuf message.
-    pub fn unpack<T>(any: &Any) -> Result<T, prost::DecodeError>
+    pub fn unpack<T>(any: &Any) -> Result<T, Error>
     where
         T: prost::Message + Default,
     {
@@ -184,17 +686,17 @@ pub mod protobuf {
     /// Deserialize an instance of type T from Protobuf message.
     pub trait Unpack {
         /// Deserialize an instance of type T.
-        fn unpack<T>(&self) -> Result<T, prost::DecodeError>
+        fn unpack<T>(&self) -> Result<T, Error>
         where
             T: prost::Message + Default;
     }
 
     impl Unpack for Any {
-        fn unpack<T>(&self) -> Result<T, prost::DecodeError>
+        fn unpack<T>(&self) -> Result<T, Error>
         where
             T: prost::Message + Default,
         {
-            T::decode(&self.value)
+            T::decode(&self.value).map_err(Error::from)
         }
     }
 }
@@ -202,58 +704,415 @@ pub mod protobuf {
 cfg_if::cfg_if! {
     if #[cfg(feature = "json")] {
         impl IntoAny for serde_json::Value {
-            fn into_any(self) -> Option<Any> {
-                serde_json::to_vec(&self).ok().map(|value| Any {
-                    value,
+            fn try_into_any(self) -> Result<Option<Any>, Error> {
+                Ok(Some(Any {
+                    value: serde_json::to_vec(&self)?,
                     type_url: format!("{}/JSON", RUST_LANG_URL),
-                })
+                }))
             }
         }
 
+        /// Serialize the given data structure as a JSON text, `None` if the encoding failed.
+        pub fn json<T>(value: &T) -> Option<Any>
+        where
+            T: ?Sized + serde::Serialize,
+        {
+            try_json(value).ok()
+        }
+
         /// Serialize the given data structure as a JSON text.
-        pub fn json<T: ?Sized>(value: &T) -> Option<Any>
+        ///
+        /// The `type_url` advertises the codec, e.g. `rust-lang.org/json/shop::Order`,
+        /// so the value may also be decoded with `dapr::codec`.
+        pub fn try_json<T>(value: &T) -> Result<Any, Error>
         where
-            T: serde::Serialize,
+            T: ?Sized + serde::Serialize,
         {
-            serde_json::to_vec(value).ok().map(|value| Any {
-                value,
-                type_url: format!("{}/{}", RUST_LANG_URL, type_name::<T>()),
+            Ok(Any {
+                value: serde_json::to_vec(value)?,
+                type_url: crate::codec::Codec::Json.type_url(type_name::<T>()),
             })
         }
 
         /// Serialize and Deserialize `Any` type as JSON text.
+        ///
+        /// `Unpack` borrows the result from the `Any` or the buffer, e.g. `&str` fields are decoded
+        /// without copying from `Bytes`, while `UnpackOwned` takes the `Any` by value and decodes
+        /// an owned result, which outlives a temporary `Any`.
         pub mod json{
+            use bytes::Bytes;
             use prost_types::Any;
+            use serde::de::DeserializeOwned;
+
+            use super::Error;
 
-            pub fn pack<T: ?Sized>(value: &T) -> Result<serde_json::Value, serde_json::error::Error>
+            pub fn pack<T>(value: &T) -> Result<serde_json::Value, Error>
             where
-                T: serde::Serialize,
+                T: ?Sized + serde::Serialize,
             {
-                serde_json::to_value(value)
+                serde_json::to_value(value).map_err(Error::from)
             }
 
             /// Deserialize an instance of type T from JSON text.
-            pub fn unpack<'a, T>(any: &'a Any) -> Result<T, serde_json::error::Error>
+            pub fn unpack<'a, T>(any: &'a Any) -> Result<T, Error>
             where
                 T: serde::Deserialize<'a>
             {
                 any.unpack()
             }
 
-            /// Deserialize an instance of type T from JSON text.
+            /// Deserialize an owned instance of type T from JSON text.
+            pub fn unpack_owned<T>(any: Any) -> Result<T, Error>
+            where
+                T: DeserializeOwned
+            {
+                any.unpack_owned()
+            }
+
+            /// Deserialize an instance of type T from JSON text in the buffer, e.g. `&[u8]` or `Bytes`.
+            pub fn unpack_bytes<'a, B, T>(buf: &'a B) -> Result<T, Error>
+            where
+                B: ?Sized + AsRef<[u8]>,
+                T: serde::Deserialize<'a>
+            {
+                serde_json::from_slice(buf.as_ref()).map_err(Error::from)
+            }
+
+            /// Deserialize an instance of type T borrowing from JSON text.
+            pub trait Unpack {
+                /// Deserialize an instance of type T.
+                fn unpack<'a, T>(&'a self) -> Result<T, Error>
+                where
+                    T: serde::Deserialize<'a>;
+            }
+
+            impl Unpack for Any {
+                fn unpack<'a, T>(&'a self) -> Result<T, Error>
+                where
+                    T: serde::Deserialize<'a>,
+                {
+                    unpack_bytes(&self.value)
+                }
+            }
+
+            impl Unpack for [u8] {
+                fn unpack<'a, T>(&'a self) -> Result<T, Error>
+                where
+                    T: serde::Deserialize<'a>,
+                {
+                    unpack_bytes(self)
+                }
+            }
+
+            impl Unpack for Bytes {
+                fn unpack<'a, T>(&'a self) -> Result<T, Error>
+                where
+                    T: serde::Deserialize<'a>,
+                {
+                    unpack_bytes(self)
+                }
+            }
+
+            /// Deserialize an owned instance of type T from JSON text.
+            pub trait UnpackOwned {
+                /// Deserialize an owned instance of type T.
+                fn unpack_owned<T>(self) -> Result<T, Error>
+                where
+                    T: DeserializeOwned;
+            }
+
+            impl UnpackOwned for Any {
+                fn unpack_owned<T>(self) -> Result<T, Error>
+                where
+                    T: DeserializeOwned,
+                {
+                    unpack_bytes(&self.value)
+                }
+            }
+
+            impl UnpackOwned for &Any {
+                fn unpack_owned<T>(self) -> Result<T, Error>
+                where
+                    T: DeserializeOwned,
+                {
+                    unpack_bytes(&self.value)
+                }
+            }
+        }
+    }
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "msgpack")] {
+        impl IntoAny for rmpv::Value {
+            fn try_into_any(self) -> Result<Option<Any>, Error> {
+                try_msgpack(&self).map(Some)
+            }
+        }
+
+        /// Serialize the given data structure as MessagePack, `None` if the encoding failed.
+        pub fn msgpack<T>(value: &T) -> Option<Any>
+        where
+            T: ?Sized + serde::Serialize,
+        {
+            try_msgpack(value).ok()
+        }
+
+        /// Serialize the given data structure as MessagePack.
+        ///
+        /// The `type_url` advertises the codec, e.g. `rust-lang.org/msgpack/shop::Order`,
+        /// so the value may also be decoded with `dapr::codec`.
+        pub fn try_msgpack<T>(value: &T) -> Result<Any, Error>
+        where
+            T: ?Sized + serde::Serialize,
+        {
+            Ok(Any {
+                value: rmp_serde::to_vec_named(value)?,
+                type_url: crate::codec::Codec::MsgPack.type_url(type_name::<T>()),
+            })
+        }
+
+        /// Serialize and Deserialize `Any` type as MessagePack.
+        ///
+        /// `Unpack` borrows the result from the `Any` or the buffer, while `UnpackOwned` takes
+        /// the `Any` by value and decodes an owned result, like `any::json`.
+        pub mod msgpack {
+            use bytes::Bytes;
+            use prost_types::Any;
+            use serde::de::DeserializeOwned;
+
+            use super::Error;
+
+            /// Serialize the given data structure as a `rmpv::Value`.
+            pub fn pack<T>(value: &T) -> Result<rmpv::Value, Error>
+            where
+                T: ?Sized + serde::Serialize,
+            {
+                rmpv::ext::to_value(value).map_err(Error::from)
+            }
+
+            /// Deserialize an instance of type T from MessagePack.
+            pub fn unpack<'a, T>(any: &'a Any) -> Result<T, Error>
+            where
+                T: serde::Deserialize<'a>,
+            {
+                any.unpack()
+            }
+
+            /// Deserialize an owned instance of type T from MessagePack.
+            pub fn unpack_owned<T>(any: Any) -> Result<T, Error>
+            where
+                T: DeserializeOwned,
+            {
+                any.unpack_owned()
+            }
+
+            /// Deserialize an instance of type T from MessagePack in the buffer, e.g. `&[u8]` or `Bytes`.
+            pub fn unpack_bytes<'a, B, T>(buf: &'a B) -> Result<T, Error>
+            where
+                B: ?Sized + AsRef<[u8]>,
+                T: serde::Deserialize<'a>,
+            {
+                rmp_serde::from_slice(buf.as_ref()).map_err(Error::from)
+            }
+
+            /// Deserialize an instance of type T borrowing from MessagePack.
+            pub trait Unpack {
+                /// Deserialize an instance of type T.
+                fn unpack<'a, T>(&'a self) -> Result<T, Error>
+                where
+                    T: serde::Deserialize<'a>;
+            }
+
+            impl Unpack for Any {
+                fn unpack<'a, T>(&'a self) -> Result<T, Error>
+                where
+                    T: serde::Deserialize<'a>,
+                {
+                    unpack_bytes(&self.value)
+                }
+            }
+
+            impl Unpack for [u8] {
+                fn unpack<'a, T>(&'a self) -> Result<T, Error>
+                where
+                    T: serde::Deserialize<'a>,
+                {
+                    unpack_bytes(self)
+                }
+            }
+
+            impl Unpack for Bytes {
+                fn unpack<'a, T>(&'a self) -> Result<T, Error>
+                where
+                    T: serde::Deserialize<'a>,
+                {
+                    unpack_bytes(self)
+                }
+            }
+
+            /// Deserialize an owned instance of type T from MessagePack.
+            pub trait UnpackOwned {
+                /// Deserialize an owned instance of type T.
+                fn unpack_owned<T>(self) -> Result<T, Error>
+                where
+                    T: DeserializeOwned;
+            }
+
+            impl UnpackOwned for Any {
+                fn unpack_owned<T>(self) -> Result<T, Error>
+                where
+                    T: DeserializeOwned,
+                {
+                    unpack_bytes(&self.value)
+                }
+            }
+
+            impl UnpackOwned for &Any {
+                fn unpack_owned<T>(self) -> Result<T, Error>
+                where
+                    T: DeserializeOwned,
+                {
+                    unpack_bytes(&self.value)
+                }
+            }
+        }
+    }
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "cbor")] {
+        impl IntoAny for serde_cbor::Value {
+            fn try_into_any(self) -> Result<Option<Any>, Error> {
+                try_cbor(&self).map(Some)
+            }
+        }
+
+        /// Serialize the given data structure as CBOR, `None` if the encoding failed.
+        pub fn cbor<T>(value: &T) -> Option<Any>
+        where
+            T: ?Sized + serde::Serialize,
+        {
+            try_cbor(value).ok()
+        }
+
+        /// Serialize the given data structure as CBOR.
+        ///
+        /// The `type_url` advertises the codec, e.g. `rust-lang.org/cbor/shop::Order`,
+        /// so the value may also be decoded with `dapr::codec`.
+        pub fn try_cbor<T>(value: &T) -> Result<Any, Error>
+        where
+            T: ?Sized + serde::Serialize,
+        {
+            // `serde_cbor` takes a sized value, which the reference is
+            Ok(Any {
+                value: serde_cbor::to_vec(&value)?,
+                type_url: crate::codec::Codec::Cbor.type_url(type_name::<T>()),
+            })
+        }
+
+        /// Serialize and Deserialize `Any` type as CBOR.
+        ///
+        /// `Unpack` borrows the result from the `Any` or the buffer, while `UnpackOwned` takes
+        /// the `Any` by value and decodes an owned result, like `any::json`.
+        pub mod cbor {
+            use bytes::Bytes;
+            use prost_types::Any;
+            use serde::de::DeserializeOwned;
+
+            use super::Error;
+
+            /// Serialize the given data structure as a `serde_cbor::Value`.
+            pub fn pack<T>(value: &T) -> Result<serde_cbor::Value, Error>
+            where
+                T: ?Sized + serde::Serialize,
+            {
+                serde_cbor::value::to_value(value).map_err(Error::from)
+            }
+
+            /// Deserialize an instance of type T from CBOR.
+            pub fn unpack<'a, T>(any: &'a Any) -> Result<T, Error>
+            where
+                T: serde::Deserialize<'a>,
+            {
+                any.unpack()
+            }
+
+            /// Deserialize an owned instance of type T from CBOR.
+            pub fn unpack_owned<T>(any: Any) -> Result<T, Error>
+            where
+                T: DeserializeOwned,
+            {
+                any.unpack_owned()
+            }
+
+            /// Deserialize an instance of type T from CBOR in the buffer, e.g. `&[u8]` or `Bytes`.
+            pub fn unpack_bytes<'a, B, T>(buf: &'a B) -> Result<T, Error>
+            where
+                B: ?Sized + AsRef<[u8]>,
+                T: serde::Deserialize<'a>,
+            {
+                serde_cbor::from_slice(buf.as_ref()).map_err(Error::from)
+            }
+
+            /// Deserialize an instance of type T borrowing from CBOR.
             pub trait Unpack {
                 /// Deserialize an instance of type T.
-                fn unpack<'a, T>(&'a self) -> Result<T, serde_json::error::Error>
+                fn unpack<'a, T>(&'a self) -> Result<T, Error>
                 where
                     T: serde::Deserialize<'a>;
             }
 
             impl Unpack for Any {
-                fn unpack<'a, T>(&'a self) -> Result<T, serde_json::error::Error>
+                fn unpack<'a, T>(&'a self) -> Result<T, Error>
+                where
+                    T: serde::Deserialize<'a>,
+                {
+                    unpack_bytes(&self.value)
+                }
+            }
+
+            impl Unpack for [u8] {
+                fn unpack<'a, T>(&'a self) -> Result<T, Error>
+                where
+                    T: serde::Deserialize<'a>,
+                {
+                    unpack_bytes(self)
+                }
+            }
+
+            impl Unpack for Bytes {
+                fn unpack<'a, T>(&'a self) -> Result<T, Error>
                 where
                     T: serde::Deserialize<'a>,
                 {
-                    serde_json::from_slice(&self.value)
+                    unpack_bytes(self)
+                }
+            }
+
+            /// Deserialize an owned instance of type T from CBOR.
+            pub trait UnpackOwned {
+                /// Deserialize an owned instance of type T.
+                fn unpack_owned<T>(self) -> Result<T, Error>
+                where
+                    T: DeserializeOwned;
+            }
+
+            impl UnpackOwned for Any {
+                fn unpack_owned<T>(self) -> Result<T, Error>
+                where
+                    T: DeserializeOwned,
+                {
+                    unpack_bytes(&self.value)
+                }
+            }
+
+            impl UnpackOwned for &Any {
+                fn unpack_owned<T>(self) -> Result<T, Error>
+                where
+                    T: DeserializeOwned,
+                {
+                    unpack_bytes(&self.value)
                 }
             }
         }
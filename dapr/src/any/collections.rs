@@ -0,0 +1,412 @@
+//! The collections and tuples of the supported types.
+//!
+//! The elements are packed into `Any` themselves, and stored in a Protobuf message which lists
+//! the distinct `type_url` of elements once, so a collection of any supported type is
+//! self-describing while staying compact, e.g. `Vec<String>` costs the `type_url` once.
+//!
+//! The sequences, `Vec`, `VecDeque`, `HashSet` and `BTreeSet`, are encoded as `rust-lang.org/v1/seq`,
+//! the maps, `HashMap` and `BTreeMap`, as `rust-lang.org/v1/map` with the keys and values interleaved,
+//! and the tuples as `rust-lang.org/v1/tuple`, so a sequence may be unpacked as another sequence.
+//!
+//! An absent element, e.g. `None` of `Option<T>`, is packed as `Any::default()`.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::iter::FromIterator;
+
+use bytes::Bytes;
+use prost::Message;
+use prost_types::Any;
+
+use super::{check_type_url, portable_type_url, wkt, Error, IntoAny, TryFromAny};
+
+const SEQ: &str = "seq";
+const MAP: &str = "map";
+const TUPLE: &str = "tuple";
+
+/// The types which may be packed as the elements of `Vec<T>`.
+///
+/// All the supported types implement it except `u8`, since `Vec<u8>` is packed as a byte buffer.
+/// It is implemented by `#[derive(dapr::IntoAny)]`, and should be implemented with the manual
+/// implementations of `IntoAny`.
+pub trait IntoElement: IntoAny {}
+
+/// The types which may be unpacked as the elements of `Vec<T>`.
+///
+/// All the supported types implement it except `u8`, since `Vec<u8>` is unpacked as a byte buffer.
+/// It is implemented by `#[derive(dapr::TryFromAny)]`, and should be implemented with the manual
+/// implementations of `TryFromAny`.
+pub trait FromElement: TryFromAny {}
+
+/// The elements of a collection or tuple.
+#[derive(Clone, PartialEq, prost_derive::Message)]
+struct Packed {
+    /// The distinct `type_url` of elements.
+    #[prost(string, repeated, tag = "1")]
+    type_urls: Vec<String>,
+    #[prost(message, repeated, tag = "2")]
+    items: Vec<Item>,
+}
+
+#[derive(Clone, PartialEq, prost_derive::Message)]
+struct Item {
+    /// The 1-based index of the `type_url` of element, or 0 for an absent element.
+    #[prost(uint32, tag = "1")]
+    ty: u32,
+    #[prost(bytes, tag = "2")]
+    value: Vec<u8>,
+}
+
+impl Packed {
+    fn push<T: IntoAny>(&mut self, value: T) -> Result<(), Error> {
+        let item = match value.try_into_any()? {
+            Some(any) => {
+                let index = match self.type_urls.iter().position(|url| *url == any.type_url) {
+                    Some(index) => index,
+                    None => {
+                        self.type_urls.push(any.type_url);
+                        self.type_urls.len() - 1
+                    }
+                };
+
+                Item {
+                    ty: index as u32 + 1,
+                    value: any.value,
+                }
+            }
+            None => Item::default(),
+        };
+
+        self.items.push(item);
+
+        Ok(())
+    }
+
+    fn pack<I>(kind: &str, values: I) -> Result<Option<Any>, Error>
+    where
+        I: IntoIterator,
+        I::Item: IntoAny,
+    {
+        let mut packed = Packed::default();
+
+        for value in values {
+            packed.push(value)?;
+        }
+
+        packed.into_any(kind).map(Some)
+    }
+
+    fn into_any(self, kind: &str) -> Result<Any, Error> {
+        let mut buf = Vec::with_capacity(self.encoded_len());
+
+        self.encode(&mut buf)?;
+
+        Ok(Any {
+            value: buf,
+            type_url: portable_type_url(kind),
+        })
+    }
+
+    fn unpack(any: Any, kind: &'static str, lenient: bool) -> Result<Vec<Any>, Error> {
+        if !lenient {
+            check_type_url(&any.type_url, &[kind])?;
+        }
+
+        let Packed { type_urls, items } = Packed::decode(&any.value)?;
+
+        items
+            .into_iter()
+            .map(|item| match item.ty {
+                0 => Ok(Any::default()),
+                ty => type_urls
+                    .get(ty as usize - 1)
+                    .map(|type_url| Any {
+                        type_url: type_url.clone(),
+                        value: item.value,
+                    })
+                    .ok_or(Error::Malformed(kind)),
+            })
+            .collect()
+    }
+
+    fn unpack_seq<T, C>(any: Any, lenient: bool) -> Result<C, Error>
+    where
+        T: TryFromAny,
+        T::Error: Into<Error>,
+        C: FromIterator<T>,
+    {
+        Packed::unpack(any, SEQ, lenient)?
+            .into_iter()
+            .map(|any| unpack_element(any, lenient))
+            .collect()
+    }
+
+    fn unpack_map<K, V, C>(any: Any, lenient: bool) -> Result<C, Error>
+    where
+        K: TryFromAny,
+        K::Error: Into<Error>,
+        V: TryFromAny,
+        V::Error: Into<Error>,
+        C: FromIterator<(K, V)>,
+    {
+        let items = Packed::unpack(any, MAP, lenient)?;
+
+        if items.len() % 2 != 0 {
+            return Err(Error::Malformed(MAP));
+        }
+
+        let mut items = items.into_iter();
+        let mut entries = Vec::with_capacity(items.len() / 2);
+
+        while let (Some(key), Some(value)) = (items.next(), items.next()) {
+            entries.push((
+                unpack_element(key, lenient)?,
+                unpack_element(value, lenient)?,
+            ));
+        }
+
+        Ok(entries.into_iter().collect())
+    }
+}
+
+fn unpack_element<T>(any: Any, lenient: bool) -> Result<T, Error>
+where
+    T: TryFromAny,
+    T::Error: Into<Error>,
+{
+    if lenient {
+        T::try_from_lenient(any)
+    } else {
+        T::try_from(any)
+    }
+    .map_err(Into::into)
+}
+
+/// The absent `Any`, e.g. an absent element of collection, is unpacked as `None`.
+impl<T> TryFromAny for Option<T>
+where
+    T: TryFromAny,
+{
+    type Error = T::Error;
+
+    fn try_from(any: Any) -> Result<Self, Self::Error> {
+        if any == Any::default() {
+            Ok(None)
+        } else {
+            T::try_from(any).map(Some)
+        }
+    }
+
+    fn try_from_lenient(any: Any) -> Result<Self, Self::Error> {
+        if any == Any::default() {
+            Ok(None)
+        } else {
+            T::try_from_lenient(any).map(Some)
+        }
+    }
+}
+
+macro_rules! impl_seq {
+    ($ty:ident < T $(: $bound:ident $(+ $bounds:ident)*)? > as $elem:ident, $from_elem:ident) => {
+        impl<T> IntoAny for $ty<T>
+        where
+            T: $elem,
+        {
+            fn try_into_any(self) -> Result<Option<Any>, Error> {
+                Packed::pack(SEQ, self)
+            }
+        }
+
+        impl<T> IntoElement for $ty<T> where T: $elem {}
+
+        impl<T> TryFromAny for $ty<T>
+        where
+            T: $from_elem $(+ $bound $(+ $bounds)*)?,
+            T::Error: Into<Error>,
+        {
+            type Error = Error;
+
+            fn try_from(any: Any) -> Result<Self, Self::Error> {
+                Packed::unpack_seq(any, false)
+            }
+
+            fn try_from_lenient(any: Any) -> Result<Self, Self::Error> {
+                Packed::unpack_seq(any, true)
+            }
+        }
+
+        impl<T> FromElement for $ty<T>
+        where
+            T: $from_elem $(+ $bound $(+ $bounds)*)?,
+            T::Error: Into<Error>,
+        {
+        }
+    };
+}
+
+impl_seq!(Vec<T> as IntoElement, FromElement);
+impl_seq!(VecDeque<T> as IntoAny, TryFromAny);
+impl_seq!(HashSet<T: Eq + Hash> as IntoAny, TryFromAny);
+impl_seq!(BTreeSet<T: Ord> as IntoAny, TryFromAny);
+
+macro_rules! impl_map {
+    ($ty:ident < K $(: $bound:ident $(+ $bounds:ident)*)? >) => {
+        impl<K, V> IntoAny for $ty<K, V>
+        where
+            K: IntoAny,
+            V: IntoAny,
+        {
+            fn try_into_any(self) -> Result<Option<Any>, Error> {
+                let mut packed = Packed::default();
+
+                for (key, value) in self {
+                    packed.push(key)?;
+                    packed.push(value)?;
+                }
+
+                packed.into_any(MAP).map(Some)
+            }
+        }
+
+        impl<K, V> IntoElement for $ty<K, V>
+        where
+            K: IntoAny,
+            V: IntoAny,
+        {
+        }
+
+        impl<K, V> TryFromAny for $ty<K, V>
+        where
+            K: TryFromAny $(+ $bound $(+ $bounds)*)?,
+            K::Error: Into<Error>,
+            V: TryFromAny,
+            V::Error: Into<Error>,
+        {
+            type Error = Error;
+
+            fn try_from(any: Any) -> Result<Self, Self::Error> {
+                Packed::unpack_map(any, false)
+            }
+
+            fn try_from_lenient(any: Any) -> Result<Self, Self::Error> {
+                Packed::unpack_map(any, true)
+            }
+        }
+
+        impl<K, V> FromElement for $ty<K, V>
+        where
+            K: TryFromAny $(+ $bound $(+ $bounds)*)?,
+            K::Error: Into<Error>,
+            V: TryFromAny,
+            V::Error: Into<Error>,
+        {
+        }
+    };
+}
+
+impl_map!(HashMap<K: Eq + Hash>);
+impl_map!(BTreeMap<K: Ord>);
+
+macro_rules! impl_tuple {
+    ($len:expr => $($name:ident $idx:tt),+) => {
+        impl<$($name),+> IntoAny for ($($name,)+)
+        where
+            $($name: IntoAny),+
+        {
+            fn try_into_any(self) -> Result<Option<Any>, Error> {
+                let mut packed = Packed::default();
+
+                $( packed.push(self.$idx)?; )+
+
+                packed.into_any(TUPLE).map(Some)
+            }
+        }
+
+        impl<$($name),+> IntoElement for ($($name,)+) where $($name: IntoAny),+ {}
+
+        impl<$($name),+> TryFromAny for ($($name,)+)
+        where
+            $($name: TryFromAny, $name::Error: Into<Error>),+
+        {
+            type Error = Error;
+
+            fn try_from(any: Any) -> Result<Self, Self::Error> {
+                Self::unpack_tuple(any, false)
+            }
+
+            fn try_from_lenient(any: Any) -> Result<Self, Self::Error> {
+                Self::unpack_tuple(any, true)
+            }
+        }
+
+        impl<$($name),+> FromElement for ($($name,)+)
+        where
+            $($name: TryFromAny, $name::Error: Into<Error>),+
+        {
+        }
+
+        impl<$($name),+> UnpackTuple for ($($name,)+)
+        where
+            $($name: TryFromAny, $name::Error: Into<Error>),+
+        {
+            fn unpack_tuple(any: Any, lenient: bool) -> Result<Self, Error> {
+                let items = Packed::unpack(any, TUPLE, lenient)?;
+
+                if items.len() != $len {
+                    return Err(Error::Malformed(TUPLE));
+                }
+
+                let mut items = items.into_iter();
+
+                Ok(($(
+                    unpack_element::<$name>(items.next().ok_or(Error::Malformed(TUPLE))?, lenient)?,
+                )+))
+            }
+        }
+    };
+}
+
+trait UnpackTuple: Sized {
+    fn unpack_tuple(any: Any, lenient: bool) -> Result<Self, Error>;
+}
+
+impl_tuple!(1 => A 0);
+impl_tuple!(2 => A 0, B 1);
+impl_tuple!(3 => A 0, B 1, C 2);
+impl_tuple!(4 => A 0, B 1, C 2, D 3);
+impl_tuple!(5 => A 0, B 1, C 2, D 3, E 4);
+impl_tuple!(6 => A 0, B 1, C 2, D 3, E 4, F 5);
+impl_tuple!(7 => A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+impl_tuple!(8 => A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+
+impl<T> IntoElement for Option<T> where T: IntoAny {}
+impl<T> FromElement for Option<T> where T: TryFromAny {}
+
+macro_rules! impl_element {
+    ($($ty:ty),*) => {
+        $(
+            impl IntoElement for $ty {}
+            impl FromElement for $ty {}
+        )*
+    };
+}
+
+impl_element!(u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+impl_element!(f32, f64, bool, (), char, String, Vec<u8>, Bytes);
+impl_element!(
+    wkt::DoubleValue,
+    wkt::FloatValue,
+    wkt::Int64Value,
+    wkt::UInt64Value,
+    wkt::Int32Value,
+    wkt::UInt32Value,
+    wkt::BoolValue,
+    wkt::StringValue,
+    wkt::BytesValue
+);
+
+impl IntoElement for &str {}
+impl IntoElement for Any {}
+
+#[cfg(feature = "json")]
+impl IntoElement for serde_json::Value {}
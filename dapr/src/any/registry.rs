@@ -0,0 +1,91 @@
+//! Dispatch `Any` values to the registered types by their `type_url`.
+
+use std::any::Any as StdAny;
+use std::collections::HashMap;
+use std::fmt;
+
+use prost_types::Any;
+
+use super::{Error, TryFromAny};
+
+/// The boxed value decoded by the registry.
+pub type BoxAny = Box<dyn StdAny + Send>;
+
+type Decoder<R> = Box<dyn Fn(Any) -> Result<R, Error> + Send + Sync>;
+
+/// The registry of types by their `type_url`, e.g. for a topic which carries several message kinds.
+///
+/// The registered types are decoded with `TryFromAny::try_from_lenient`, since the `type_url` was
+/// already matched, so any `type_url` may be registered, e.g. `orders/v1`. The decoded value is
+/// passed to the handler of type, whose output is returned by `decode`. With `register`, the value is returned
+/// as `BoxAny`, which may be downcast to the type.
+pub struct AnyRegistry<R = BoxAny> {
+    decoders: HashMap<String, Decoder<R>>,
+}
+
+impl<R> Default for AnyRegistry<R> {
+    fn default() -> Self {
+        AnyRegistry {
+            decoders: HashMap::new(),
+        }
+    }
+}
+
+impl<R> fmt::Debug for AnyRegistry<R> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.decoders.keys()).finish()
+    }
+}
+
+impl<R> AnyRegistry<R> {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Decode the values of `type_url` as type T, and pass them to the handler,
+    /// replacing the previous handler of `type_url`.
+    pub fn handle<T, F, U>(mut self, type_url: U, handler: F) -> Self
+    where
+        T: TryFromAny,
+        T::Error: Into<Error>,
+        F: Fn(T) -> R + Send + Sync + 'static,
+        U: Into<String>,
+    {
+        self.decoders.insert(
+            type_url.into(),
+            Box::new(move |any| T::try_from_lenient(any).map(&handler).map_err(Into::into)),
+        );
+        self
+    }
+
+    /// The type is registered with the `type_url`.
+    pub fn contains(&self, type_url: &str) -> bool {
+        self.decoders.contains_key(type_url)
+    }
+
+    /// The registered `type_url`.
+    pub fn type_urls(&self) -> impl Iterator<Item = &str> {
+        self.decoders.keys().map(String::as_str)
+    }
+
+    /// Decode the value with the type registered for its `type_url`, and pass it to the handler.
+    pub fn decode(&self, any: Any) -> Result<R, Error> {
+        match self.decoders.get(&any.type_url) {
+            Some(decoder) => decoder(any),
+            None => Err(Error::UnknownType(any.type_url)),
+        }
+    }
+}
+
+impl AnyRegistry<BoxAny> {
+    /// Decode the values of `type_url` as type T, replacing the previous type of `type_url`.
+    pub fn register<T, U>(self, type_url: U) -> Self
+    where
+        T: TryFromAny + Send + 'static,
+        T::Error: Into<Error>,
+        U: Into<String>,
+    {
+        self.handle(type_url, |value: T| Box::new(value) as BoxAny)
+    }
+}
@@ -0,0 +1,135 @@
+//! The well-known wrapper types of `google/protobuf/wrappers.proto`.
+//!
+//! The wrappers are the values exchanged by the Dapr SDKs of other languages, whose `type_url`
+//! is e.g. `type.googleapis.com/google.protobuf.StringValue`.
+
+use prost::Message;
+use prost_types::Any;
+
+use super::{Error, IntoAny, TryFromAny};
+
+/// The `type_url` prefix of the Protobuf messages.
+pub const GOOGLE_APIS_URL: &str = "type.googleapis.com";
+
+/// The `type_url` names a Protobuf message, e.g. `type.googleapis.com/google.protobuf.StringValue`.
+pub fn is_google_type_url(type_url: &str) -> bool {
+    google_type_name(type_url).is_some()
+}
+
+/// The full name of the Protobuf message in the `type_url`.
+pub fn google_type_name(type_url: &str) -> Option<&str> {
+    type_url
+        .strip_prefix(GOOGLE_APIS_URL)
+        .and_then(|name| name.strip_prefix('/'))
+}
+
+macro_rules! well_known_types {
+    ($( $(#[$attr:meta])* $name:ident($ty:ty, $kind:ident); )*) => {
+        $(
+            $(#[$attr])*
+            #[derive(Clone, PartialEq, prost_derive::Message)]
+            pub struct $name {
+                /// The wrapped value.
+                #[prost($kind, tag = "1")]
+                pub value: $ty,
+            }
+
+            impl $name {
+                /// The `type_url` of the wrapper.
+                pub const TYPE_URL: &'static str =
+                    concat!("type.googleapis.com/google.protobuf.", stringify!($name));
+            }
+
+            impl From<$ty> for $name {
+                fn from(value: $ty) -> Self {
+                    $name { value }
+                }
+            }
+
+            impl IntoAny for $name {
+                fn try_into_any(self) -> Result<Option<Any>, Error> {
+                    let mut buf = Vec::with_capacity(self.encoded_len());
+
+                    self.encode(&mut buf)?;
+
+                    // prost skips `-0.0` like the default `0.0`, which loses the sign
+                    if self.value.is_negative_zero() {
+                        prost::encoding::$kind::encode(1, &self.value, &mut buf);
+                    }
+
+                    Ok(Some(Any {
+                        value: buf,
+                        type_url: Self::TYPE_URL.to_owned(),
+                    }))
+                }
+            }
+
+            impl TryFromAny for $name {
+                type Error = Error;
+
+                fn try_from(any: Any) -> Result<Self, Self::Error> {
+                    if any.type_url != Self::TYPE_URL {
+                        return Err(Error::TypeMismatch {
+                            expected: Self::TYPE_URL,
+                            actual: any.type_url,
+                        });
+                    }
+
+                    Self::try_from_lenient(any)
+                }
+
+                fn try_from_lenient(any: Any) -> Result<Self, Self::Error> {
+                    Self::decode(&any.value).map_err(Error::from)
+                }
+            }
+        )*
+    };
+}
+
+/// The wrapped values, which may be equal to the default without being encoded the same.
+trait SignedZero {
+    fn is_negative_zero(&self) -> bool {
+        false
+    }
+}
+
+impl SignedZero for f64 {
+    fn is_negative_zero(&self) -> bool {
+        *self == 0.0 && self.is_sign_negative()
+    }
+}
+
+impl SignedZero for f32 {
+    fn is_negative_zero(&self) -> bool {
+        *self == 0.0 && self.is_sign_negative()
+    }
+}
+
+impl SignedZero for i64 {}
+impl SignedZero for u64 {}
+impl SignedZero for i32 {}
+impl SignedZero for u32 {}
+impl SignedZero for bool {}
+impl SignedZero for String {}
+impl SignedZero for Vec<u8> {}
+
+well_known_types! {
+    /// Wrapper message for `double`.
+    DoubleValue(f64, double);
+    /// Wrapper message for `float`.
+    FloatValue(f32, float);
+    /// Wrapper message for `int64`.
+    Int64Value(i64, int64);
+    /// Wrapper message for `uint64`.
+    UInt64Value(u64, uint64);
+    /// Wrapper message for `int32`.
+    Int32Value(i32, int32);
+    /// Wrapper message for `uint32`.
+    UInt32Value(u32, uint32);
+    /// Wrapper message for `bool`.
+    BoolValue(bool, bool);
+    /// Wrapper message for `string`.
+    StringValue(String, string);
+    /// Wrapper message for `bytes`.
+    BytesValue(Vec<u8>, bytes);
+}
@@ -0,0 +1,214 @@
+//! Host the `DaprClient` service of the app.
+//!
+//! The `App` serves the service and the gRPC health checking service on the port which the
+//! Dapr runtime calls the app on. When the process receives `SIGTERM` or `Ctrl-C`, the app stops
+//! accepting new calls and drains the in-flight `OnInvoke` and `OnTopicEvent` calls before exiting.
+
+use std::env;
+use std::future::Future;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use futures_util::{future, StreamExt};
+use hyper::server::conn::AddrIncoming;
+use tonic::{
+    body::BoxBody,
+    codegen::{http, BoxFuture, HyperBody, Never, Service},
+};
+
+use crate::{
+    client::server::{DaprClient, DaprClientServerSvc},
+    error::{Error, Result},
+    health::{health_service, server::HealthServerSvc, HealthService},
+};
+
+const DAPR_APP_PORT: &str = "DAPR_APP_PORT";
+const DAPR_APP_ID: &str = "DAPR_APP_ID";
+const APP_ID: &str = "APP_ID";
+
+const HEALTH_PATH: &str = "/grpc.health.v1.Health/";
+
+/// The app hosting the `DaprClient` service, e.g. `dapr::Router` or a generated `{Trait}Client`.
+pub struct App<S> {
+    service: S,
+    app_id: Option<String>,
+    host: IpAddr,
+}
+
+impl<S> App<S>
+where
+    S: DaprClient,
+{
+    /// Create the app hosting the service.
+    pub fn new(service: S) -> Self {
+        App {
+            service,
+            app_id: None,
+            host: Ipv4Addr::LOCALHOST.into(),
+        }
+    }
+
+    /// Set the app ID, which is read from `DAPR_APP_ID` or `APP_ID` by default.
+    pub fn with_app_id<I: Into<String>>(mut self, app_id: I) -> Self {
+        self.app_id = Some(app_id.into());
+        self
+    }
+
+    /// Set the host which `bind_from_env` binds to, `127.0.0.1` by default since the Dapr runtime
+    /// calls the app from its sidecar, e.g. `0.0.0.0` to accept the calls on all interfaces.
+    pub fn with_host<H: Into<IpAddr>>(mut self, host: H) -> Self {
+        self.host = host.into();
+        self
+    }
+
+    /// Bind the app to the address, the port `0` binds to a free port.
+    pub fn bind(self, addr: SocketAddr) -> Result<BoundApp<S>> {
+        let incoming = AddrIncoming::bind(&addr)?;
+
+        Ok(BoundApp {
+            service: self.service,
+            app_id: self.app_id,
+            incoming,
+        })
+    }
+
+    /// Bind the app to the port in `DAPR_APP_PORT` on the host, `127.0.0.1` by default.
+    pub fn bind_from_env(self) -> Result<BoundApp<S>> {
+        let port = env::var(DAPR_APP_PORT).map_err(|_| Error::MissingEnv(DAPR_APP_PORT))?;
+        let port = port.parse().map_err(|_| Error::InvalidAppPort(port))?;
+        let app = match self.app_id {
+            Some(_) => self,
+            None => App {
+                app_id: env::var(DAPR_APP_ID).or_else(|_| env::var(APP_ID)).ok(),
+                ..self
+            },
+        };
+
+        let host = app.host;
+
+        app.bind(SocketAddr::from((host, port)))
+    }
+
+    /// Serve the app on the address until it is shut down by a signal.
+    pub async fn serve(self, addr: SocketAddr) -> Result<()> {
+        self.bind(addr)?.serve().await
+    }
+
+    /// Serve the app on the port in `DAPR_APP_PORT` until it is shut down by a signal.
+    pub async fn serve_from_env(self) -> Result<()> {
+        self.bind_from_env()?.serve().await
+    }
+}
+
+/// The app bound to a local address, which is not serving yet.
+pub struct BoundApp<S> {
+    service: S,
+    app_id: Option<String>,
+    incoming: AddrIncoming,
+}
+
+impl<S> BoundApp<S>
+where
+    S: DaprClient,
+{
+    /// The local address which the app is bound to.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.incoming.local_addr()
+    }
+
+    /// The app ID, if it was set or provided by the Dapr runtime, which is also a service name
+    /// known by the health service.
+    pub fn app_id(&self) -> Option<&str> {
+        self.app_id.as_deref()
+    }
+
+    /// Serve the app until it receives `SIGTERM` or `Ctrl-C`.
+    pub async fn serve(self) -> Result<()> {
+        let signal = shutdown_signal()?;
+
+        self.serve_with_shutdown(signal).await
+    }
+
+    /// Serve the app until the signal completes, then drain the in-flight calls.
+    pub async fn serve_with_shutdown<F>(self, signal: F) -> Result<()>
+    where
+        F: Future<Output = ()>,
+    {
+        let (reporter, health) = health_service(self.app_id);
+        let routes = Routes {
+            service: Arc::new(self.service),
+            health: Arc::new(health),
+        };
+        let make_service = hyper::service::make_service_fn(move |_| {
+            let routes = routes.clone();
+
+            async move { Ok::<_, Never>(routes) }
+        });
+        let signal = async move {
+            signal.await;
+            reporter.set_not_serving();
+        };
+
+        hyper::Server::builder(self.incoming)
+            .http2_only(true)
+            .serve(make_service)
+            .with_graceful_shutdown(signal)
+            .await
+            .map_err(Error::from)
+    }
+}
+
+/// Dispatch the health checks to the health service, and other calls to the `DaprClient` service.
+struct Routes<S> {
+    service: Arc<S>,
+    health: Arc<HealthService>,
+}
+
+impl<S> Clone for Routes<S> {
+    fn clone(&self) -> Self {
+        Routes {
+            service: self.service.clone(),
+            health: self.health.clone(),
+        }
+    }
+}
+
+impl<S> Service<http::Request<HyperBody>> for Routes<S>
+where
+    S: DaprClient,
+{
+    type Response = http::Response<BoxBody>;
+    type Error = Never;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<std::result::Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: http::Request<HyperBody>) -> Self::Future {
+        if req.uri().path().starts_with(HEALTH_PATH) {
+            HealthServerSvc::new(self.health.clone()).call(req)
+        } else {
+            DaprClientServerSvc::new(self.service.clone()).call(req)
+        }
+    }
+}
+
+/// Complete when the process receives `SIGTERM` or `Ctrl-C`.
+fn shutdown_signal() -> Result<impl Future<Output = ()>> {
+    let ctrl_c = tokio_net::signal::ctrl_c()?.into_future();
+
+    #[cfg(unix)]
+    let terminate = {
+        use tokio_net::signal::unix::{signal, SignalKind};
+
+        signal(SignalKind::terminate())?.into_future()
+    };
+    #[cfg(not(unix))]
+    let terminate = future::pending::<()>();
+
+    Ok(async move {
+        future::select(ctrl_c, terminate).await;
+    })
+}
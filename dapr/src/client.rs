@@ -2,6 +2,17 @@
 
 //! The Dapr client interface.
 
+use std::collections::HashMap;
+use std::convert::{TryFrom, TryInto};
+use std::fmt;
+use std::future::Future;
+use std::marker::PhantomData;
+
+use prost_types::Any;
+
+use crate::any::{self, IntoAny, TryFromAny};
+use crate::cloud_event::{CloudEvent, EventData};
+
 tonic::include_proto!("daprclient");
 
 #[tonic::async_trait]
@@ -13,6 +24,117 @@ pub trait Events {
     async fn on_topic_event(&self, event: CloudEventEnvelope) -> Result<(), Self::Error>;
 }
 
+/// Handle the events published to a topic.
+#[tonic::async_trait]
+pub trait TopicHandler: Send + Sync {
+    async fn on_topic_event(&self, event: CloudEventEnvelope) -> Result<(), tonic::Status>;
+}
+
+#[tonic::async_trait]
+impl<F, R> TopicHandler for F
+where
+    F: Fn(CloudEventEnvelope) -> R + Send + Sync,
+    R: Future<Output = Result<(), tonic::Status>> + Send,
+{
+    async fn on_topic_event(&self, event: CloudEventEnvelope) -> Result<(), tonic::Status> {
+        self(event).await
+    }
+}
+
+/// Decode the events as the typed events before calling the handler.
+struct Typed<F, T> {
+    handler: F,
+    phantom: PhantomData<fn(T)>,
+}
+
+#[tonic::async_trait]
+impl<F, R, T> TopicHandler for Typed<F, T>
+where
+    T: EventData + Send + 'static,
+    F: Fn(CloudEvent<T>) -> R + Send + Sync,
+    R: Future<Output = Result<(), tonic::Status>> + Send,
+{
+    async fn on_topic_event(&self, event: CloudEventEnvelope) -> Result<(), tonic::Status> {
+        let event = CloudEvent::try_from(event)?;
+
+        (self.handler)(event).await
+    }
+}
+
+/// Dispatch the topic events to the handlers registered per topic.
+///
+/// The topic subscriptions are derived from the registered handlers.
+#[derive(Default)]
+pub struct EventRouter {
+    topics: Vec<(String, Box<dyn TopicHandler>)>,
+}
+
+impl EventRouter {
+    /// Create an empty router.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the handler for a topic, replacing the previous one.
+    pub fn topic<S, H>(mut self, topic: S, handler: H) -> Self
+    where
+        S: Into<String>,
+        H: TopicHandler + 'static,
+    {
+        let topic = topic.into();
+        let handler = Box::new(handler);
+
+        if let Some(pos) = self.topics.iter().position(|(name, _)| *name == topic) {
+            self.topics[pos].1 = handler;
+        } else {
+            self.topics.push((topic, handler));
+        }
+
+        self
+    }
+
+    /// Register the handler for a topic, which receives the events with the typed data.
+    pub fn event<S, T, F, R>(self, topic: S, handler: F) -> Self
+    where
+        S: Into<String>,
+        T: EventData + Send + 'static,
+        F: Fn(CloudEvent<T>) -> R + Send + Sync + 'static,
+        R: Future<Output = Result<(), tonic::Status>> + Send,
+    {
+        self.topic(
+            topic,
+            Typed {
+                handler,
+                phantom: PhantomData,
+            },
+        )
+    }
+
+    /// The subscribed topics, in the order they were registered.
+    pub fn topics(&self) -> impl Iterator<Item = &str> {
+        self.topics.iter().map(|(topic, _)| topic.as_str())
+    }
+}
+
+#[tonic::async_trait]
+impl Events for EventRouter {
+    type Error = tonic::Status;
+
+    async fn topic_subscriptions(&self) -> Result<Vec<String>, Self::Error> {
+        Ok(self.topics().map(String::from).collect())
+    }
+
+    async fn on_topic_event(&self, event: CloudEventEnvelope) -> Result<(), Self::Error> {
+        match self.topics.iter().find(|(topic, _)| *topic == event.topic) {
+            Some((_, handler)) => handler.on_topic_event(event).await,
+            None => Err(tonic::Status::new(
+                tonic::Code::NotFound,
+                format!("topic `{}` not subscribed", event.topic),
+            )),
+        }
+    }
+}
+
 #[tonic::async_trait]
 pub trait Bindings {
     type Error: std::error::Error;
@@ -24,3 +146,524 @@ pub trait Bindings {
         event: BindingEventEnvelope,
     ) -> Result<BindingResponseEnvelope, Self::Error>;
 }
+
+/// Handle the events triggered by an input binding.
+#[tonic::async_trait]
+pub trait BindingHandler: Send + Sync {
+    async fn on_binding_event(
+        &self,
+        event: BindingEventEnvelope,
+    ) -> Result<BindingResponseEnvelope, tonic::Status>;
+}
+
+#[tonic::async_trait]
+impl<F, R, O> BindingHandler for F
+where
+    F: Fn(BindingEventEnvelope) -> R + Send + Sync,
+    R: Future<Output = Result<O, tonic::Status>> + Send,
+    O: TryInto<BindingResponseEnvelope>,
+    O::Error: fmt::Display,
+{
+    async fn on_binding_event(
+        &self,
+        event: BindingEventEnvelope,
+    ) -> Result<BindingResponseEnvelope, tonic::Status> {
+        self(event).await.and_then(into_response)
+    }
+}
+
+/// Convert the output of a binding handler, failing if the response can't be encoded.
+fn into_response<O>(res: O) -> Result<BindingResponseEnvelope, tonic::Status>
+where
+    O: TryInto<BindingResponseEnvelope>,
+    O::Error: fmt::Display,
+{
+    res.try_into()
+        .map_err(|err| tonic::Status::new(tonic::Code::Internal, err.to_string()))
+}
+
+/// An event triggered by an input binding, with the typed data.
+///
+/// The data of bindings doesn't record the Rust type, and is unpacked without checking the `type_url`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BindingEvent<T> {
+    /// The name of the binding.
+    pub name: String,
+    /// The event payload.
+    pub data: Option<T>,
+    /// The metadata of the event.
+    pub metadata: HashMap<String, String>,
+}
+
+impl<T> TryFrom<BindingEventEnvelope> for BindingEvent<T>
+where
+    T: TryFromAny,
+    T::Error: fmt::Display,
+{
+    type Error = tonic::Status;
+
+    fn try_from(envelope: BindingEventEnvelope) -> Result<Self, Self::Error> {
+        let BindingEventEnvelope {
+            name,
+            data,
+            metadata,
+        } = envelope;
+        let data = data
+            .map(T::try_from_lenient)
+            .transpose()
+            .map_err(|err| tonic::Status::new(tonic::Code::InvalidArgument, err.to_string()))?;
+
+        Ok(BindingEvent {
+            name,
+            data,
+            metadata,
+        })
+    }
+}
+
+#[tonic::async_trait]
+impl<F, R, O, T> BindingHandler for Typed<F, T>
+where
+    T: TryFromAny + Send + 'static,
+    T::Error: fmt::Display,
+    F: Fn(BindingEvent<T>) -> R + Send + Sync,
+    R: Future<Output = Result<O, tonic::Status>> + Send,
+    O: TryInto<BindingResponseEnvelope>,
+    O::Error: fmt::Display,
+{
+    async fn on_binding_event(
+        &self,
+        event: BindingEventEnvelope,
+    ) -> Result<BindingResponseEnvelope, tonic::Status> {
+        let event = BindingEvent::try_from(event)?;
+
+        (self.handler)(event).await.and_then(into_response)
+    }
+}
+
+/// The concurrency of the output bindings and state saving.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Concurrency {
+    /// The output bindings are invoked in parallel.
+    Parallel,
+    /// The output bindings are invoked one by one.
+    Sequential,
+}
+
+impl Concurrency {
+    /// The name of the concurrency mode.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Concurrency::Parallel => "parallel",
+            Concurrency::Sequential => "sequential",
+        }
+    }
+}
+
+/// The response of an input binding event.
+///
+/// The data may be sent to the output bindings, and state objects may be saved to the state store.
+///
+/// The first encode error of the data or state objects is kept, and returned
+/// when the response is converted into `BindingResponseEnvelope`.
+#[derive(Debug, Default)]
+pub struct BindingResponse {
+    data: Option<Any>,
+    to: Vec<String>,
+    state: Vec<State>,
+    concurrency: Option<Concurrency>,
+    error: Option<any::Error>,
+}
+
+impl BindingResponse {
+    /// Create an empty response.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a response with the data.
+    pub fn data<D: IntoAny>(data: D) -> Self {
+        let mut res = Self::default();
+
+        match data.try_into_any() {
+            Ok(data) => res.data = data,
+            Err(err) => res.error = Some(err),
+        }
+
+        res
+    }
+
+    /// Send the data to the output bindings.
+    pub fn forward_to<I, S>(mut self, bindings: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.to.extend(bindings.into_iter().map(Into::into));
+        self
+    }
+
+    /// Save an array of state objects, e.g. `State` or `(key, value)`.
+    pub fn save_state<I, S>(mut self, states: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: TryInto<State>,
+        S::Error: Into<any::Error>,
+    {
+        for state in states {
+            match state.try_into() {
+                Ok(state) => self.state.push(state),
+                Err(err) => {
+                    self.error.get_or_insert(err.into());
+                }
+            }
+        }
+
+        self
+    }
+
+    /// Invoke the output bindings in parallel.
+    pub fn parallel(self) -> Self {
+        self.concurrency(Concurrency::Parallel)
+    }
+
+    /// Invoke the output bindings one by one.
+    pub fn sequential(self) -> Self {
+        self.concurrency(Concurrency::Sequential)
+    }
+
+    /// Set the concurrency of the output bindings.
+    pub fn concurrency(mut self, concurrency: Concurrency) -> Self {
+        self.concurrency = Some(concurrency);
+        self
+    }
+}
+
+impl TryFrom<BindingResponse> for BindingResponseEnvelope {
+    type Error = any::Error;
+
+    fn try_from(res: BindingResponse) -> Result<Self, Self::Error> {
+        if let Some(err) = res.error {
+            return Err(err);
+        }
+
+        Ok(BindingResponseEnvelope {
+            data: res.data,
+            to: res.to,
+            state: res.state,
+            concurrency: res
+                .concurrency
+                .map(|concurrency| concurrency.as_str().to_owned())
+                .unwrap_or_default(),
+        })
+    }
+}
+
+impl From<()> for BindingResponseEnvelope {
+    fn from(_: ()) -> Self {
+        Default::default()
+    }
+}
+
+impl<'a> From<&'a State> for State {
+    fn from(state: &'a State) -> Self {
+        state.clone()
+    }
+}
+
+impl<K, V> TryFrom<(K, V)> for State
+where
+    K: Into<String>,
+    V: IntoAny,
+{
+    type Error = any::Error;
+
+    fn try_from((key, value): (K, V)) -> Result<Self, Self::Error> {
+        Ok(State {
+            key: key.into(),
+            value: value.try_into_any()?,
+            ..Default::default()
+        })
+    }
+}
+
+impl<'a, K, V> TryFrom<&'a (K, V)> for State
+where
+    K: Clone + Into<String>,
+    V: Clone + IntoAny,
+{
+    type Error = any::Error;
+
+    fn try_from(state: &'a (K, V)) -> Result<Self, Self::Error> {
+        state.clone().try_into()
+    }
+}
+
+/// Dispatch the binding events to the handlers registered per binding.
+///
+/// The bindings subscriptions are derived from the registered handlers.
+#[derive(Default)]
+pub struct BindingRouter {
+    bindings: Vec<(String, Box<dyn BindingHandler>)>,
+}
+
+impl BindingRouter {
+    /// Create an empty router.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the handler for a binding, replacing the previous one.
+    pub fn binding<S, H>(mut self, name: S, handler: H) -> Self
+    where
+        S: Into<String>,
+        H: BindingHandler + 'static,
+    {
+        let name = name.into();
+        let handler = Box::new(handler);
+
+        if let Some(pos) = self
+            .bindings
+            .iter()
+            .position(|(binding, _)| *binding == name)
+        {
+            self.bindings[pos].1 = handler;
+        } else {
+            self.bindings.push((name, handler));
+        }
+
+        self
+    }
+
+    /// Register the handler for a binding, which receives the events with the typed data.
+    pub fn event<S, T, F, R, O>(self, name: S, handler: F) -> Self
+    where
+        S: Into<String>,
+        T: TryFromAny + Send + 'static,
+        T::Error: fmt::Display,
+        F: Fn(BindingEvent<T>) -> R + Send + Sync + 'static,
+        R: Future<Output = Result<O, tonic::Status>> + Send,
+        O: TryInto<BindingResponseEnvelope>,
+        O::Error: fmt::Display,
+    {
+        self.binding(
+            name,
+            Typed {
+                handler,
+                phantom: PhantomData,
+            },
+        )
+    }
+
+    /// The subscribed bindings, in the order they were registered.
+    pub fn bindings(&self) -> impl Iterator<Item = &str> {
+        self.bindings.iter().map(|(name, _)| name.as_str())
+    }
+}
+
+#[tonic::async_trait]
+impl Bindings for BindingRouter {
+    type Error = tonic::Status;
+
+    async fn bindings_subscriptions(&self) -> Result<Vec<String>, Self::Error> {
+        Ok(self.bindings().map(String::from).collect())
+    }
+
+    async fn on_binding_event(
+        &self,
+        event: BindingEventEnvelope,
+    ) -> Result<BindingResponseEnvelope, Self::Error> {
+        match self.bindings.iter().find(|(name, _)| *name == event.name) {
+            Some((_, handler)) => handler.on_binding_event(event).await,
+            None => Err(tonic::Status::new(
+                tonic::Code::NotFound,
+                format!("binding `{}` not subscribed", event.name),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::cloud_event::TEXT_PLAIN;
+
+    fn envelope(topic: &str, content_type: &str, data: &str) -> CloudEventEnvelope {
+        CloudEventEnvelope {
+            id: "1".to_owned(),
+            source: "test".to_owned(),
+            r#type: "com.example.event".to_owned(),
+            spec_version: "1.0".to_owned(),
+            data_content_type: content_type.to_owned(),
+            topic: topic.to_owned(),
+            data: any::pack(data),
+        }
+    }
+
+    fn router(seen: &Arc<Mutex<Vec<String>>>) -> EventRouter {
+        let orders = seen.clone();
+        let users = seen.clone();
+
+        EventRouter::new()
+            .event("orders", move |event: CloudEvent<String>| {
+                let seen = orders.clone();
+
+                async move {
+                    seen.lock()
+                        .unwrap()
+                        .push(format!("orders: {}", event.data.unwrap()));
+                    Ok(())
+                }
+            })
+            .topic("users", move |event: CloudEventEnvelope| {
+                let seen = users.clone();
+
+                async move {
+                    seen.lock().unwrap().push(format!("users: {}", event.id));
+                    Ok(())
+                }
+            })
+    }
+
+    #[tokio::test]
+    async fn subscriptions_follow_registration_order() {
+        let seen = Arc::new(Mutex::new(vec![]));
+        let router = router(&seen).topic("orders", |_| async { Ok(()) });
+
+        assert_eq!(
+            router.topic_subscriptions().await.unwrap(),
+            vec!["orders".to_owned(), "users".to_owned()]
+        );
+    }
+
+    #[tokio::test]
+    async fn dispatch_event_to_topic_handler() {
+        let seen = Arc::new(Mutex::new(vec![]));
+        let router = router(&seen);
+
+        router
+            .on_topic_event(envelope("orders", TEXT_PLAIN, "apple"))
+            .await
+            .unwrap();
+        router
+            .on_topic_event(envelope("users", TEXT_PLAIN, "alice"))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec!["orders: apple".to_owned(), "users: 1".to_owned()]
+        );
+    }
+
+    #[tokio::test]
+    async fn unknown_topic_is_not_found() {
+        let seen = Arc::new(Mutex::new(vec![]));
+        let router = router(&seen);
+
+        let status = router
+            .on_topic_event(envelope("payments", TEXT_PLAIN, "apple"))
+            .await
+            .unwrap_err();
+
+        assert_eq!(status.code(), tonic::Code::NotFound);
+        assert!(seen.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn undecodable_event_is_invalid_argument() {
+        let seen = Arc::new(Mutex::new(vec![]));
+        let router = router(&seen);
+
+        let status = router
+            .on_topic_event(envelope("orders", "application/protobuf", "apple"))
+            .await
+            .unwrap_err();
+
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+
+        let status = router
+            .on_topic_event(CloudEventEnvelope {
+                id: String::new(),
+                ..envelope("orders", TEXT_PLAIN, "apple")
+            })
+            .await
+            .unwrap_err();
+
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+        assert!(seen.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn binding_response_chain() {
+        let res = BindingResponse::data("apple")
+            .forward_to(vec!["queue", "storage"])
+            .save_state(&[("order", 1u32), ("total", 2u32)])
+            .parallel();
+
+        let envelope = BindingResponseEnvelope::try_from(res).unwrap();
+
+        assert_eq!(envelope.data, "apple".into_any());
+        assert_eq!(envelope.to, vec!["queue".to_owned(), "storage".to_owned()]);
+        assert_eq!(
+            envelope
+                .state
+                .iter()
+                .map(|state| state.key.as_str())
+                .collect::<Vec<_>>(),
+            vec!["order", "total"]
+        );
+        assert_eq!(envelope.state[1].value, 2u32.into_any());
+        assert_eq!(envelope.concurrency, "parallel");
+    }
+
+    #[derive(Clone)]
+    struct Unencodable(&'static str);
+
+    impl IntoAny for Unencodable {
+        fn try_into_any(self) -> Result<Option<Any>, any::Error> {
+            Err(any::Error::Malformed(self.0))
+        }
+    }
+
+    #[test]
+    fn binding_response_keeps_first_error() {
+        let res = BindingResponse::data(Unencodable("data"))
+            .save_state(&[("key", Unencodable("state"))])
+            .sequential();
+
+        match BindingResponseEnvelope::try_from(res) {
+            Err(any::Error::Malformed(name)) => assert_eq!(name, "data"),
+            res => panic!("unexpected result: {:?}", res),
+        }
+
+        let res = BindingResponse::new().save_state(&[
+            ("first", Unencodable("first")),
+            ("second", Unencodable("second")),
+        ]);
+
+        match BindingResponseEnvelope::try_from(res) {
+            Err(any::Error::Malformed(name)) => assert_eq!(name, "first"),
+            res => panic!("unexpected result: {:?}", res),
+        }
+    }
+
+    #[tokio::test]
+    async fn unencodable_binding_response_is_internal() {
+        let router = BindingRouter::new().binding("queue", |_| async {
+            Ok(BindingResponse::data(Unencodable("data")))
+        });
+
+        let status = router
+            .on_binding_event(BindingEventEnvelope {
+                name: "queue".to_owned(),
+                ..Default::default()
+            })
+            .await
+            .unwrap_err();
+
+        assert_eq!(status.code(), tonic::Code::Internal);
+    }
+}
@@ -0,0 +1,608 @@
+//! The CloudEvents model of the topic events.
+
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+use prost_types::Any;
+use thiserror::Error;
+
+use crate::any::{self, IntoAny, Unpack};
+use crate::client::CloudEventEnvelope;
+
+/// The content type of JSON data.
+pub const APPLICATION_JSON: &str = "application/json";
+/// The content type of Protobuf data.
+pub const APPLICATION_PROTOBUF: &str = "application/protobuf";
+/// The content type of binary data.
+pub const APPLICATION_OCTET_STREAM: &str = "application/octet-stream";
+/// The content type of text data.
+pub const TEXT_PLAIN: &str = "text/plain";
+
+/// CloudEvent error
+#[derive(Error, Debug)]
+pub enum Error {
+    /// The required attribute is missing.
+    #[error("missing required attribute `{0}`")]
+    MissingAttribute(&'static str),
+
+    /// The spec version is not supported.
+    #[error("unsupported spec version `{0}`")]
+    UnsupportedSpecVersion(String),
+
+    /// The content type can't be decoded as the expected data.
+    #[error("unexpected content type `{actual}`, expected {expected}")]
+    UnexpectedContentType {
+        expected: &'static str,
+        actual: String,
+    },
+
+    /// The data is missing.
+    #[error("missing data")]
+    MissingData,
+
+    /// Protobuf decode error
+    #[error("decode error")]
+    Decode(#[from] prost::DecodeError),
+
+    /// Protobuf encode error
+    #[error("encode error")]
+    Encode(#[from] prost::EncodeError),
+
+    /// Any error
+    #[error("Any error")]
+    Any(#[from] crate::any::Error),
+
+    /// JSON error
+    #[cfg(feature = "json")]
+    #[error("JSON error")]
+    Json(#[from] serde_json::error::Error),
+}
+
+impl From<Error> for tonic::Status {
+    fn from(err: Error) -> Self {
+        tonic::Status::new(tonic::Code::InvalidArgument, err.to_string())
+    }
+}
+
+/// The version of the CloudEvents specification which the event uses.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SpecVersion {
+    /// CloudEvents v0.3
+    V03,
+    /// CloudEvents v1.0
+    V10,
+}
+
+impl SpecVersion {
+    /// The version string of the specification.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SpecVersion::V03 => "0.3",
+            SpecVersion::V10 => "1.0",
+        }
+    }
+}
+
+impl fmt::Display for SpecVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for SpecVersion {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "0.3" => Ok(SpecVersion::V03),
+            "1.0" => Ok(SpecVersion::V10),
+            _ => Err(Error::UnsupportedSpecVersion(s.to_owned())),
+        }
+    }
+}
+
+/// A CloudEvent with the typed data.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CloudEvent<T = Data> {
+    /// Identifies the event.
+    pub id: String,
+    /// Identifies the context in which an event happened.
+    pub source: String,
+    /// The type of event related to the originating occurrence.
+    pub ty: String,
+    /// The version of the CloudEvents specification which the event uses.
+    pub spec_version: SpecVersion,
+    /// Content type of the data value.
+    pub data_content_type: String,
+    /// The topic which the event was published to.
+    pub topic: String,
+    /// The event payload.
+    pub data: Option<T>,
+}
+
+impl<T> CloudEvent<T> {
+    /// Map the data of event to another type.
+    pub fn map<U, F>(self, f: F) -> CloudEvent<U>
+    where
+        F: FnOnce(T) -> U,
+    {
+        CloudEvent {
+            id: self.id,
+            source: self.source,
+            ty: self.ty,
+            spec_version: self.spec_version,
+            data_content_type: self.data_content_type,
+            topic: self.topic,
+            data: self.data.map(f),
+        }
+    }
+
+    /// Consumes the event, returning the data.
+    pub fn into_data(self) -> Option<T> {
+        self.data
+    }
+}
+
+impl<T> TryFrom<CloudEventEnvelope> for CloudEvent<T>
+where
+    T: EventData,
+{
+    type Error = Error;
+
+    fn try_from(envelope: CloudEventEnvelope) -> Result<Self, Self::Error> {
+        let CloudEventEnvelope {
+            id,
+            source,
+            r#type,
+            spec_version,
+            data_content_type,
+            topic,
+            data,
+        } = envelope;
+
+        if id.is_empty() {
+            return Err(Error::MissingAttribute("id"));
+        }
+        if source.is_empty() {
+            return Err(Error::MissingAttribute("source"));
+        }
+        if r#type.is_empty() {
+            return Err(Error::MissingAttribute("type"));
+        }
+        if spec_version.is_empty() {
+            return Err(Error::MissingAttribute("specversion"));
+        }
+
+        let spec_version = spec_version.parse()?;
+        let data_content_type = if data_content_type.is_empty() {
+            APPLICATION_JSON.to_owned()
+        } else {
+            data_content_type
+        };
+        let data = data
+            .map(|data| T::decode(&data_content_type, data))
+            .transpose()?;
+
+        Ok(CloudEvent {
+            id,
+            source,
+            ty: r#type,
+            spec_version,
+            data_content_type,
+            topic,
+            data,
+        })
+    }
+}
+
+impl<T> TryFrom<CloudEvent<T>> for CloudEventEnvelope
+where
+    T: EventData,
+{
+    type Error = Error;
+
+    fn try_from(event: CloudEvent<T>) -> Result<Self, Self::Error> {
+        let CloudEvent {
+            id,
+            source,
+            ty,
+            spec_version,
+            data_content_type,
+            topic,
+            data,
+        } = event;
+
+        let (data_content_type, data) = match data {
+            Some(data) => {
+                let (content_type, data) = data.encode()?;
+
+                (content_type.to_owned(), Some(data))
+            }
+            None => (data_content_type, None),
+        };
+
+        Ok(CloudEventEnvelope {
+            id,
+            source,
+            r#type: ty,
+            spec_version: spec_version.to_string(),
+            data_content_type,
+            topic,
+            data,
+        })
+    }
+}
+
+/// The data of event which can be decoded and encoded according to the content type.
+pub trait EventData: Sized {
+    /// Decode the data with the content type.
+    fn decode(content_type: &str, data: Any) -> Result<Self, Error>;
+
+    /// Encode the data, returns the content type and payload.
+    fn encode(self) -> Result<(&'static str, Any), Error>;
+}
+
+/// The media type of the content type, without parameters.
+fn media_type(content_type: &str) -> String {
+    content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase()
+}
+
+#[cfg(feature = "json")]
+fn is_json(content_type: &str) -> bool {
+    let media_type = media_type(content_type);
+
+    media_type == APPLICATION_JSON || media_type == "text/json" || media_type.ends_with("+json")
+}
+
+fn is_protobuf(content_type: &str) -> bool {
+    let media_type = media_type(content_type);
+
+    media_type == APPLICATION_PROTOBUF || media_type == "application/x-protobuf"
+}
+
+fn is_text(content_type: &str) -> bool {
+    media_type(content_type).starts_with("text/")
+}
+
+/// The dynamic data of event, decoded according to the content type.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Data {
+    /// JSON value
+    #[cfg(feature = "json")]
+    Json(serde_json::Value),
+    /// Text
+    Text(String),
+    /// Protobuf message
+    Protobuf(Any),
+    /// Binary data
+    Binary(Vec<u8>),
+}
+
+impl EventData for Data {
+    fn decode(content_type: &str, data: Any) -> Result<Self, Error> {
+        cfg_if::cfg_if! {
+            if #[cfg(feature = "json")] {
+                if is_json(content_type) {
+                    return EventData::decode(content_type, data).map(Data::Json);
+                }
+            }
+        }
+
+        if is_protobuf(content_type) {
+            Ok(Data::Protobuf(data))
+        } else if is_text(content_type) {
+            EventData::decode(content_type, data).map(Data::Text)
+        } else {
+            EventData::decode(content_type, data).map(Data::Binary)
+        }
+    }
+
+    fn encode(self) -> Result<(&'static str, Any), Error> {
+        match self {
+            #[cfg(feature = "json")]
+            Data::Json(value) => value.encode(),
+            Data::Text(text) => text.encode(),
+            Data::Protobuf(any) => Ok((APPLICATION_PROTOBUF, any)),
+            Data::Binary(data) => data.encode(),
+        }
+    }
+}
+
+impl EventData for String {
+    fn decode(content_type: &str, data: Any) -> Result<Self, Error> {
+        if is_text(content_type) {
+            // the content type describes the data instead of the `type_url`
+            data.unpack_lenient().map_err(Error::from)
+        } else {
+            Err(Error::UnexpectedContentType {
+                expected: TEXT_PLAIN,
+                actual: content_type.to_owned(),
+            })
+        }
+    }
+
+    fn encode(self) -> Result<(&'static str, Any), Error> {
+        self.try_into_any()?
+            .map(|any| (TEXT_PLAIN, any))
+            .ok_or(Error::MissingData)
+    }
+}
+
+impl EventData for Vec<u8> {
+    fn decode(_content_type: &str, data: Any) -> Result<Self, Error> {
+        Ok(data.value)
+    }
+
+    fn encode(self) -> Result<(&'static str, Any), Error> {
+        any::pack(self)
+            .map(|any| (APPLICATION_OCTET_STREAM, any))
+            .ok_or(Error::MissingData)
+    }
+}
+
+/// The data encoded as Protobuf message.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Protobuf<T>(pub T);
+
+impl<T> EventData for Protobuf<T>
+where
+    T: prost::Message + Default,
+{
+    fn decode(content_type: &str, data: Any) -> Result<Self, Error> {
+        if is_protobuf(content_type) {
+            any::protobuf::unpack(&data)
+                .map(Protobuf)
+                .map_err(Error::from)
+        } else {
+            Err(Error::UnexpectedContentType {
+                expected: APPLICATION_PROTOBUF,
+                actual: content_type.to_owned(),
+            })
+        }
+    }
+
+    fn encode(self) -> Result<(&'static str, Any), Error> {
+        let mut buf = Vec::with_capacity(self.0.encoded_len());
+
+        self.0.encode(&mut buf)?;
+
+        any::pack(buf)
+            .map(|any| (APPLICATION_PROTOBUF, any))
+            .ok_or(Error::MissingData)
+    }
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "json")] {
+        /// The data encoded as JSON text.
+        #[derive(Clone, Debug, Default, PartialEq)]
+        pub struct Json<T>(pub T);
+
+        impl<T> EventData for Json<T>
+        where
+            T: serde::Serialize + serde::de::DeserializeOwned,
+        {
+            fn decode(content_type: &str, data: Any) -> Result<Self, Error> {
+                if is_json(content_type) {
+                    serde_json::from_slice(&data.value)
+                        .map(Json)
+                        .map_err(Error::from)
+                } else {
+                    Err(Error::UnexpectedContentType {
+                        expected: APPLICATION_JSON,
+                        actual: content_type.to_owned(),
+                    })
+                }
+            }
+
+            fn encode(self) -> Result<(&'static str, Any), Error> {
+                let value = serde_json::to_vec(&self.0)?;
+
+                any::pack(value)
+                    .map(|any| (APPLICATION_JSON, any))
+                    .ok_or(Error::MissingData)
+            }
+        }
+
+        impl EventData for serde_json::Value {
+            fn decode(content_type: &str, data: Any) -> Result<Self, Error> {
+                Json::decode(content_type, data).map(|Json(value)| value)
+            }
+
+            fn encode(self) -> Result<(&'static str, Any), Error> {
+                Json(self).encode()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryInto;
+
+    use super::*;
+
+    fn envelope(content_type: &str, data: &[u8]) -> CloudEventEnvelope {
+        CloudEventEnvelope {
+            id: "1".to_owned(),
+            source: "test".to_owned(),
+            r#type: "com.example.event".to_owned(),
+            spec_version: "1.0".to_owned(),
+            data_content_type: content_type.to_owned(),
+            topic: "orders".to_owned(),
+            data: any::pack(data),
+        }
+    }
+
+    #[test]
+    fn missing_attributes() {
+        let missing = |envelope: CloudEventEnvelope| match CloudEvent::<Data>::try_from(envelope) {
+            Err(Error::MissingAttribute(name)) => name,
+            res => panic!("unexpected result: {:?}", res),
+        };
+
+        let event = envelope(TEXT_PLAIN, b"apple");
+
+        assert_eq!(
+            missing(CloudEventEnvelope {
+                id: String::new(),
+                ..event.clone()
+            }),
+            "id"
+        );
+        assert_eq!(
+            missing(CloudEventEnvelope {
+                source: String::new(),
+                ..event.clone()
+            }),
+            "source"
+        );
+        assert_eq!(
+            missing(CloudEventEnvelope {
+                r#type: String::new(),
+                ..event.clone()
+            }),
+            "type"
+        );
+        assert_eq!(
+            missing(CloudEventEnvelope {
+                spec_version: String::new(),
+                ..event
+            }),
+            "specversion"
+        );
+    }
+
+    #[test]
+    fn spec_versions() {
+        for &version in &[SpecVersion::V03, SpecVersion::V10] {
+            let event = CloudEvent::<Data>::try_from(CloudEventEnvelope {
+                spec_version: version.to_string(),
+                ..envelope(TEXT_PLAIN, b"apple")
+            })
+            .unwrap();
+
+            assert_eq!(event.spec_version, version);
+        }
+
+        match CloudEvent::<Data>::try_from(CloudEventEnvelope {
+            spec_version: "2.0".to_owned(),
+            ..envelope(TEXT_PLAIN, b"apple")
+        }) {
+            Err(Error::UnsupportedSpecVersion(version)) => assert_eq!(version, "2.0"),
+            res => panic!("unexpected result: {:?}", res),
+        }
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn empty_content_type_is_json() {
+        let event = CloudEvent::<Data>::try_from(envelope("", br#"{"id":1}"#)).unwrap();
+
+        assert_eq!(event.data_content_type, APPLICATION_JSON);
+        assert_eq!(event.data, Some(Data::Json(serde_json::json!({"id": 1}))));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json_media_types() {
+        for &content_type in &[
+            "application/cloudevents+json",
+            "application/json; charset=utf-8",
+            "Application/JSON",
+            "text/json",
+        ] {
+            let event =
+                CloudEvent::<Json<serde_json::Value>>::try_from(envelope(content_type, b"[1,2]"))
+                    .unwrap();
+
+            assert_eq!(event.data_content_type, content_type);
+            assert_eq!(event.into_data(), Some(Json(serde_json::json!([1, 2]))));
+        }
+    }
+
+    #[test]
+    fn media_type_parameters() {
+        let event =
+            CloudEvent::<Data>::try_from(envelope("text/plain; charset=utf-8", b"apple")).unwrap();
+
+        assert_eq!(event.data, Some(Data::Text("apple".to_owned())));
+
+        let event = CloudEvent::<Data>::try_from(envelope("image/png", b"apple")).unwrap();
+
+        assert_eq!(event.data, Some(Data::Binary(b"apple".to_vec())));
+    }
+
+    #[test]
+    fn unexpected_content_type() {
+        match CloudEvent::<String>::try_from(envelope(APPLICATION_PROTOBUF, b"apple")) {
+            Err(Error::UnexpectedContentType { expected, actual }) => {
+                assert_eq!(expected, TEXT_PLAIN);
+                assert_eq!(actual, APPLICATION_PROTOBUF);
+            }
+            res => panic!("unexpected result: {:?}", res),
+        }
+
+        match CloudEvent::<Protobuf<Any>>::try_from(envelope(TEXT_PLAIN, b"apple")) {
+            Err(Error::UnexpectedContentType { expected, actual }) => {
+                assert_eq!(expected, APPLICATION_PROTOBUF);
+                assert_eq!(actual, TEXT_PLAIN);
+            }
+            res => panic!("unexpected result: {:?}", res),
+        }
+    }
+
+    #[test]
+    fn envelope_round_trip() {
+        let event = CloudEvent {
+            id: "1".to_owned(),
+            source: "test".to_owned(),
+            ty: "com.example.event".to_owned(),
+            spec_version: SpecVersion::V03,
+            data_content_type: String::new(),
+            topic: "orders".to_owned(),
+            data: Some("apple".to_owned()),
+        };
+
+        let envelope: CloudEventEnvelope = event.clone().try_into().unwrap();
+
+        assert_eq!(envelope.r#type, "com.example.event");
+        assert_eq!(envelope.spec_version, "0.3");
+        assert_eq!(envelope.data_content_type, TEXT_PLAIN);
+
+        let decoded = CloudEvent::<String>::try_from(envelope).unwrap();
+
+        assert_eq!(
+            decoded,
+            CloudEvent {
+                data_content_type: TEXT_PLAIN.to_owned(),
+                ..event
+            }
+        );
+    }
+
+    #[test]
+    fn envelope_without_data() {
+        let event = CloudEvent::<String> {
+            id: "1".to_owned(),
+            source: "test".to_owned(),
+            ty: "com.example.event".to_owned(),
+            spec_version: SpecVersion::V10,
+            data_content_type: TEXT_PLAIN.to_owned(),
+            topic: "orders".to_owned(),
+            data: None,
+        };
+
+        let envelope = CloudEventEnvelope::try_from(event.clone()).unwrap();
+
+        assert_eq!(envelope.data, None);
+        assert_eq!(envelope.data_content_type, TEXT_PLAIN);
+        assert_eq!(CloudEvent::try_from(envelope).unwrap(), event);
+    }
+}
@@ -0,0 +1,215 @@
+//! The wire codec of the generated service and stub arguments.
+//!
+//! The codec is advertised in the `type_url` of `Any`, e.g. `rust-lang.org/msgpack/FooBarArgs`,
+//! so the service may accept the arguments encoded with any of the enabled codecs.
+//!
+//! The Protobuf messages of the other Dapr SDKs, e.g. `type.googleapis.com/shop.Order`,
+//! are recognized as the `protobuf` codec.
+
+use std::any::type_name;
+use std::fmt;
+use std::str::FromStr;
+
+use prost_types::Any;
+
+use crate::any::{
+    wkt::{google_type_name, GOOGLE_APIS_URL},
+    Encoding,
+};
+
+pub use crate::any::Error;
+
+const RUST_LANG_URL: &str = "rust-lang.org";
+
+/// The wire codec.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Codec {
+    /// JSON text
+    Json,
+    /// Protobuf message
+    Protobuf,
+    /// MessagePack
+    MsgPack,
+    /// CBOR
+    Cbor,
+}
+
+impl Codec {
+    /// The name of codec.
+    pub fn name(self) -> &'static str {
+        match self {
+            Codec::Json => "json",
+            Codec::Protobuf => "protobuf",
+            Codec::MsgPack => "msgpack",
+            Codec::Cbor => "cbor",
+        }
+    }
+
+    /// The codec advertised in the `type_url`.
+    pub fn from_type_url(type_url: &str) -> Option<Self> {
+        if google_type_name(type_url).is_some() {
+            return Some(Codec::Protobuf);
+        }
+
+        let mut parts = type_url.splitn(3, '/');
+
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some(RUST_LANG_URL), Some(name), Some(_)) => name.parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// The codec advertised in the `type_url` of `Any`, or the default codec.
+    pub fn detect(any: &Any, default: Codec) -> Self {
+        Self::from_type_url(&any.type_url).unwrap_or(default)
+    }
+
+    /// The `type_url` of the named type encoded with the codec, e.g. `rust-lang.org/json/shop.Order`.
+    pub fn type_url(self, name: &str) -> String {
+        format!("{}/{}/{}", RUST_LANG_URL, self.name(), name)
+    }
+}
+
+impl fmt::Display for Codec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Codec {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(Codec::Json),
+            "protobuf" => Ok(Codec::Protobuf),
+            "msgpack" => Ok(Codec::MsgPack),
+            "cbor" => Ok(Codec::Cbor),
+            _ => Err(Error::UnsupportedCodec(s.to_owned())),
+        }
+    }
+}
+
+/// The serde codecs referenced by the generated code, which exist only with the codec feature,
+/// so a service declared with a disabled codec fails to compile.
+#[doc(hidden)]
+pub mod features {
+    use super::Codec;
+
+    #[cfg(feature = "json")]
+    pub const JSON: Codec = Codec::Json;
+    #[cfg(feature = "msgpack")]
+    pub const MSGPACK: Codec = Codec::MsgPack;
+    #[cfg(feature = "cbor")]
+    pub const CBOR: Codec = Codec::Cbor;
+}
+
+/// Serialize the given data structure with the codec.
+pub fn encode<T>(codec: Codec, value: &T) -> Result<Any, Error>
+where
+    T: serde::Serialize,
+{
+    encode_named(codec, type_name::<T>(), value)
+}
+
+/// Serialize the given data structure with the codec, under the stable type name.
+pub fn encode_named<T>(codec: Codec, name: &str, value: &T) -> Result<Any, Error>
+where
+    T: serde::Serialize,
+{
+    let value = match codec {
+        #[cfg(feature = "json")]
+        Codec::Json => serde_json::to_vec(value)?,
+        #[cfg(feature = "msgpack")]
+        Codec::MsgPack => rmp_serde::to_vec_named(value)?,
+        #[cfg(feature = "cbor")]
+        Codec::Cbor => serde_cbor::to_vec(value)?,
+        _ => return Err(Error::UnsupportedCodec(codec.name().to_owned())),
+    };
+
+    Ok(Any {
+        value,
+        type_url: codec.type_url(name),
+    })
+}
+
+/// Deserialize an instance of type T with the codec.
+pub fn decode<T>(codec: Codec, any: &Any) -> Result<T, Error>
+where
+    T: serde::de::DeserializeOwned,
+{
+    match codec {
+        #[cfg(feature = "json")]
+        Codec::Json => serde_json::from_slice(&any.value).map_err(Error::from),
+        #[cfg(feature = "msgpack")]
+        Codec::MsgPack => rmp_serde::from_slice(&any.value).map_err(Error::from),
+        #[cfg(feature = "cbor")]
+        Codec::Cbor => serde_cbor::from_slice(&any.value).map_err(Error::from),
+        _ => Err(Error::UnsupportedCodec(codec.name().to_owned())),
+    }
+}
+
+/// Serialize the given message as Protobuf.
+pub fn encode_message<T>(value: &T) -> Result<Any, Error>
+where
+    T: prost::Message,
+{
+    encode_message_with(Codec::Protobuf.type_url(type_name::<T>()), value)
+}
+
+/// Serialize the given message as Protobuf with the encoding, under the stable type name.
+///
+/// With `Encoding::WellKnown`, the name is taken as the full name of the Protobuf message,
+/// e.g. `type.googleapis.com/shop.Order`, so the message may be read by the other Dapr SDKs.
+pub fn encode_message_named<T>(encoding: Encoding, name: &str, value: &T) -> Result<Any, Error>
+where
+    T: prost::Message,
+{
+    let type_url = match encoding {
+        Encoding::Rust => Codec::Protobuf.type_url(name),
+        Encoding::WellKnown => format!("{}/{}", GOOGLE_APIS_URL, name),
+    };
+
+    encode_message_with(type_url, value)
+}
+
+fn encode_message_with<T>(type_url: String, value: &T) -> Result<Any, Error>
+where
+    T: prost::Message,
+{
+    let mut buf = Vec::with_capacity(value.encoded_len());
+
+    value.encode(&mut buf)?;
+
+    Ok(Any {
+        value: buf,
+        type_url,
+    })
+}
+
+/// Deserialize an instance of message T from Protobuf.
+pub fn decode_message<T>(any: &Any) -> Result<T, Error>
+where
+    T: prost::Message + Default,
+{
+    T::decode(&any.value).map_err(Error::from)
+}
+
+/// Check the `type_url` of `Any` names the type, and return the advertised codec.
+pub fn check_type_url(any: &Any, name: &'static str) -> Result<Codec, Error> {
+    if google_type_name(&any.type_url) == Some(name) {
+        return Ok(Codec::Protobuf);
+    }
+
+    let mut parts = any.type_url.splitn(3, '/');
+
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(RUST_LANG_URL), Some(codec), Some(ty)) if ty == name => codec.parse(),
+        // the JSON values were written as `rust-lang.org/{name}` before the codec was advertised
+        (Some(RUST_LANG_URL), Some(ty), None) if ty == name => Ok(Codec::Json),
+        _ => Err(Error::TypeMismatch {
+            expected: name,
+            actual: any.type_url.clone(),
+        }),
+    }
+}
@@ -0,0 +1,146 @@
+//! The context of the service method invocation.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use tonic::{
+    metadata::{MetadataKey, MetadataMap, MetadataValue},
+    Request, Response,
+};
+
+use crate::{client::InvokeEnvelope, error::Result};
+
+const GRPC_TIMEOUT: &str = "grpc-timeout";
+const TRACE_PARENT: &str = "traceparent";
+const TRACE_STATE: &str = "tracestate";
+const CALLER_APP_ID: &str = "dapr-caller-app-id";
+
+/// The context of the service method invocation.
+///
+/// A service method receives the context when it declares a `ctx: &dapr::Context` parameter,
+/// which is not a part of the serialized arguments.
+#[derive(Debug)]
+pub struct Context {
+    method: String,
+    metadata: HashMap<String, String>,
+    headers: MetadataMap,
+    deadline: Option<Instant>,
+    response_metadata: Mutex<MetadataMap>,
+}
+
+impl Context {
+    /// Create the context from the `OnInvoke` request.
+    pub fn from_request(request: &Request<InvokeEnvelope>) -> Self {
+        let headers = request.metadata().clone();
+        let deadline = headers
+            .get(GRPC_TIMEOUT)
+            .and_then(|value| value.to_str().ok())
+            .and_then(parse_timeout)
+            .map(|timeout| Instant::now() + timeout);
+
+        Context {
+            method: request.get_ref().method.clone(),
+            metadata: request.get_ref().metadata.clone(),
+            headers,
+            deadline,
+            response_metadata: Mutex::new(MetadataMap::new()),
+        }
+    }
+
+    /// The name of invoked method.
+    pub fn method(&self) -> &str {
+        &self.method
+    }
+
+    /// The metadata of the invocation.
+    pub fn metadata(&self) -> &HashMap<String, String> {
+        &self.metadata
+    }
+
+    /// The headers of the gRPC request.
+    pub fn headers(&self) -> &MetadataMap {
+        &self.headers
+    }
+
+    /// Get the ASCII header of the gRPC request.
+    pub fn header(&self, key: &str) -> Option<&str> {
+        self.headers.get(key).and_then(|value| value.to_str().ok())
+    }
+
+    /// The deadline of the invocation, if the caller set a timeout.
+    pub fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    /// The time remaining before the deadline.
+    pub fn timeout(&self) -> Option<Duration> {
+        self.deadline
+            .map(|deadline| deadline.saturating_duration_since(Instant::now()))
+    }
+
+    /// The W3C `traceparent` header of trace context.
+    pub fn trace_parent(&self) -> Option<&str> {
+        self.header(TRACE_PARENT)
+    }
+
+    /// The W3C `tracestate` header of trace context.
+    pub fn trace_state(&self) -> Option<&str> {
+        self.header(TRACE_STATE)
+    }
+
+    /// The app ID of caller, if it was provided by the Dapr runtime.
+    pub fn caller_app_id(&self) -> Option<&str> {
+        self.header(CALLER_APP_ID)
+    }
+
+    /// Set the metadata of the response.
+    pub fn set_response_metadata<K, V>(&self, key: K, value: V) -> Result<()>
+    where
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let key = MetadataKey::from_bytes(key.as_ref().as_bytes())?;
+        let value = MetadataValue::from_str(value.as_ref())?;
+
+        self.response_metadata
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .insert(key, value);
+
+        Ok(())
+    }
+
+    /// Create the response with the metadata set by the service method.
+    #[doc(hidden)]
+    pub fn into_response<T>(self, message: T) -> Response<T> {
+        let mut response = Response::new(message);
+
+        *response.metadata_mut() = self
+            .response_metadata
+            .into_inner()
+            .unwrap_or_else(|err| err.into_inner());
+
+        response
+    }
+}
+
+/// Parse the `grpc-timeout` header, e.g. `100m` for 100 milliseconds.
+fn parse_timeout(s: &str) -> Option<Duration> {
+    if s.len() < 2 {
+        return None;
+    }
+
+    let (value, unit) = s.split_at(s.len() - 1);
+    let value = value.parse::<u64>().ok()?;
+
+    match unit {
+        "H" => Some(Duration::from_secs(value * 60 * 60)),
+        "M" => Some(Duration::from_secs(value * 60)),
+        "S" => Some(Duration::from_secs(value)),
+        "m" => Some(Duration::from_millis(value)),
+        "u" => Some(Duration::from_micros(value)),
+        "n" => Some(Duration::from_nanos(value)),
+        _ => None,
+    }
+}
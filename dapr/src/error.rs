@@ -9,11 +9,108 @@ pub enum Error {
     #[error("transport error")]
     Transport(#[from] tonic::transport::Error),
 
+    /// gRPC server error
+    #[error("server error")]
+    Server(#[from] hyper::Error),
+
+    /// I/O error
+    #[error("I/O error")]
+    Io(#[from] std::io::Error),
+
+    /// The environment variable is not set.
+    #[error("environment variable `{0}` is not set")]
+    MissingEnv(&'static str),
+
+    /// The app port is not a valid port number.
+    #[error("invalid app port `{0}`")]
+    InvalidAppPort(String),
+
     /// gRPC status
     #[error("gRPC status")]
     Grpc(#[from] tonic::Status),
 
+    /// Protobuf decode error
+    #[error("decode error")]
+    Decode(#[from] prost::DecodeError),
+
+    /// CloudEvent error
+    #[error("CloudEvent error")]
+    CloudEvent(#[from] crate::cloud_event::Error),
+
+    /// The method name is served by more than one service.
+    #[error("duplicate method `{0}`")]
+    DuplicateMethod(String),
+
+    /// Any error
+    #[error("Any error")]
+    Any(#[from] crate::any::Error),
+
+    /// Invalid metadata key
+    #[error("invalid metadata key")]
+    InvalidMetadataKey(#[from] tonic::metadata::errors::InvalidMetadataKey),
+
+    /// Invalid metadata value
+    #[error("invalid metadata value")]
+    InvalidMetadataValue(#[from] tonic::metadata::errors::InvalidMetadataValue),
+
     /// JSON error
     #[error("JSON error")]
     Json(#[from] serde_json::error::Error),
 }
+
+/// Convert the error of service method to the gRPC status.
+pub trait IntoStatus {
+    /// Performs the conversion.
+    fn into_status(self) -> tonic::Status;
+}
+
+impl IntoStatus for tonic::Status {
+    fn into_status(self) -> tonic::Status {
+        self
+    }
+}
+
+impl IntoStatus for Error {
+    fn into_status(self) -> tonic::Status {
+        match self {
+            Error::Grpc(status) => status,
+            err => tonic::Status::new(tonic::Code::Internal, err.to_string()),
+        }
+    }
+}
+
+impl IntoStatus for String {
+    fn into_status(self) -> tonic::Status {
+        tonic::Status::new(tonic::Code::Unknown, self)
+    }
+}
+
+impl IntoStatus for &str {
+    fn into_status(self) -> tonic::Status {
+        tonic::Status::new(tonic::Code::Unknown, self)
+    }
+}
+
+/// Convert the gRPC status of service method back to the error.
+pub trait FromStatus {
+    /// Performs the conversion.
+    fn from_status(status: tonic::Status) -> Self;
+}
+
+impl FromStatus for tonic::Status {
+    fn from_status(status: tonic::Status) -> Self {
+        status
+    }
+}
+
+impl FromStatus for Error {
+    fn from_status(status: tonic::Status) -> Self {
+        Error::Grpc(status)
+    }
+}
+
+impl FromStatus for String {
+    fn from_status(status: tonic::Status) -> Self {
+        status.message().to_owned()
+    }
+}
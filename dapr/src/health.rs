@@ -0,0 +1,103 @@
+//! The gRPC health checking service of the app.
+//!
+//! The app reports `SERVING` until it starts shutting down, then `NOT_SERVING` while the
+//! in-flight calls are drained.
+
+use std::sync::Arc;
+
+use futures_util::StreamExt;
+use tokio_sync::watch;
+use tonic::{codegen::BoxStream, Code, Request, Response, Status};
+
+tonic::include_proto!("grpc.health.v1");
+
+pub use health_check_response::ServingStatus;
+
+const DAPR_CLIENT_SERVICE: &str = "daprclient.DaprClient";
+
+/// Create the health service and the reporter of its status, the service is also known by the app ID.
+pub fn health_service(app_id: Option<String>) -> (HealthReporter, HealthService) {
+    let (tx, rx) = watch::channel(ServingStatus::Serving);
+
+    (
+        HealthReporter(tx),
+        HealthService {
+            status: rx,
+            app_id: app_id.map(Arc::from),
+        },
+    )
+}
+
+/// Update the status reported by the health service.
+#[derive(Debug)]
+pub struct HealthReporter(watch::Sender<ServingStatus>);
+
+impl HealthReporter {
+    /// Report the app is serving.
+    pub fn set_serving(&self) {
+        let _ = self.0.broadcast(ServingStatus::Serving);
+    }
+
+    /// Report the app is not serving, e.g. when it is shutting down.
+    pub fn set_not_serving(&self) {
+        let _ = self.0.broadcast(ServingStatus::NotServing);
+    }
+}
+
+/// The `grpc.health.v1.Health` service, which knows the overall status, `daprclient.DaprClient`
+/// and the app ID.
+#[derive(Clone, Debug)]
+pub struct HealthService {
+    status: watch::Receiver<ServingStatus>,
+    app_id: Option<Arc<str>>,
+}
+
+impl HealthService {
+    fn check_service(&self, service: &str) -> Result<(), Status> {
+        if service.is_empty()
+            || service == DAPR_CLIENT_SERVICE
+            || self.app_id.as_deref() == Some(service)
+        {
+            Ok(())
+        } else {
+            Err(Status::new(
+                Code::NotFound,
+                format!("service `{}` not found", service),
+            ))
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl server::Health for HealthService {
+    async fn check(
+        &self,
+        request: Request<HealthCheckRequest>,
+    ) -> Result<Response<HealthCheckResponse>, Status> {
+        self.check_service(&request.get_ref().service)?;
+
+        let status = *self.status.get_ref();
+
+        Ok(Response::new(HealthCheckResponse {
+            status: status as i32,
+        }))
+    }
+
+    type WatchStream = BoxStream<HealthCheckResponse>;
+
+    async fn watch(
+        &self,
+        request: Request<HealthCheckRequest>,
+    ) -> Result<Response<Self::WatchStream>, Status> {
+        self.check_service(&request.get_ref().service)?;
+
+        // the current status is sent first, followed by the changes
+        let stream = self.status.clone().map(|status| {
+            Ok(HealthCheckResponse {
+                status: status as i32,
+            })
+        });
+
+        Ok(Response::new(Box::pin(stream)))
+    }
+}
@@ -6,29 +6,50 @@
 pub extern crate bytes;
 #[doc(hidden)]
 pub extern crate prost_types;
+#[cfg(feature = "schema")]
+#[doc(hidden)]
+pub extern crate schemars;
 #[doc(hidden)]
 pub extern crate serde;
 #[doc(hidden)]
 pub extern crate tonic;
 
 #[cfg(feature = "mocking")]
-#[macro_use]
 pub extern crate simulacrum;
 
 #[doc(hidden)]
 pub use async_trait::async_trait;
-pub use dapr_derive::{service, stub};
+pub use dapr_derive::{interface, service, stub, IntoAny, TryFromAny};
 
 pub mod any;
+pub mod app;
 pub mod client;
+pub mod cloud_event;
+pub mod codec;
+mod context;
 mod error;
+pub mod health;
+pub mod outbox;
+pub mod router;
 pub mod runtime;
+#[cfg(feature = "schema")]
+pub mod schema;
 
-pub use error::Error;
+pub use app::App;
+pub use cloud_event::CloudEvent;
+pub use context::Context;
+pub use error::{Error, FromStatus, IntoStatus};
+pub use router::Router;
 
+#[cfg(feature = "cbor")]
+#[doc(inline)]
+pub use any::cbor;
 #[cfg(feature = "json")]
 #[doc(inline)]
 pub use any::json;
+#[cfg(feature = "msgpack")]
+#[doc(inline)]
+pub use any::msgpack;
 #[doc(inline)]
 pub use any::{pack, protobuf, Unpack};
 #[doc(inline)]
@@ -0,0 +1,273 @@
+//! Transactional outbox for publishing events through the state store.
+//!
+//! Dapr only guarantees at least once semantics for `publish_event`, and a process may crash
+//! between saving its state and publishing the matching event. The outbox saves the pending
+//! events in the same `save_state` call as the business state, and a relay publishes them
+//! afterwards, deleting each event once it has been published.
+//!
+//! The pending events are tracked by an index saved under the outbox name, so a relay started
+//! after a restart will pick up every event that was saved but never published.
+//!
+//! **Note:** the business state and the events are only saved atomically when the state store
+//! applies a bulk save as a single transaction. Otherwise the index is saved first with its etag,
+//! so a concurrent save loses the race before writing anything, and the events are saved before
+//! the business state, so an interrupted save may publish an event without its state, but never
+//! saves the state without its event.
+
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use prost_types::Any;
+use tonic::codegen::{Body, HttpBody, StdError};
+
+use crate::{
+    any::{protobuf, try_protobuf, IntoAny},
+    error::{Error, Result},
+    runtime::{IntoStateRequest, Runtime, StateRequest},
+};
+
+/// A pending event saved in the outbox.
+#[derive(Clone, PartialEq, prost_derive::Message)]
+pub struct Event {
+    /// The unique ID of the event.
+    #[prost(string, tag = "1")]
+    pub id: String,
+    /// The topic to publish the event.
+    #[prost(string, tag = "2")]
+    pub topic: String,
+    /// The payload of the event.
+    #[prost(message, optional, tag = "3")]
+    pub data: Option<Any>,
+}
+
+/// The IDs of the pending events, in the order they were saved.
+#[derive(Clone, PartialEq, prost_derive::Message)]
+struct Index {
+    #[prost(string, repeated, tag = "1")]
+    ids: Vec<String>,
+}
+
+/// A transactional outbox backed by the state store.
+#[derive(Clone, Debug)]
+pub struct Outbox {
+    name: String,
+}
+
+impl Outbox {
+    /// Create an outbox which saves its events under the given name.
+    pub fn new<S: Into<String>>(name: S) -> Self {
+        Outbox { name: name.into() }
+    }
+
+    /// The name of the outbox.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn event_key(&self, id: &str) -> String {
+        format!("{}/{}", self.name, id)
+    }
+}
+
+impl Outbox {
+    /// Save an array of state objects together with the events to publish.
+    pub async fn save_state<T, I, S, E, K, D>(
+        &self,
+        runtime: &mut Runtime<T>,
+        requests: I,
+        events: E,
+    ) -> Result<()>
+    where
+        T: tonic::client::GrpcService<tonic::body::BoxBody>,
+        T::ResponseBody: Body + HttpBody + Send + 'static,
+        T::Error: Into<StdError>,
+        <T::ResponseBody as HttpBody>::Error: Into<StdError> + Send,
+        <T::ResponseBody as HttpBody>::Data: Into<bytes::Bytes> + Send,
+        I: IntoIterator<Item = S>,
+        S: IntoStateRequest,
+        E: IntoIterator<Item = (K, D)>,
+        K: Into<String>,
+        D: IntoAny,
+    {
+        let (mut index, etag) = self.index(runtime).await?;
+        let mut event_requests = vec![];
+
+        for (topic, data) in events {
+            let event = Event {
+                id: next_id(),
+                topic: topic.into(),
+                data: data.try_into_any_with(runtime.encoding())?,
+            };
+
+            index.ids.push(event.id.clone());
+            event_requests.push(StateRequest {
+                key: self.event_key(&event.id),
+                value: Some(try_protobuf(&event)?),
+                ..Default::default()
+            });
+        }
+
+        // the index goes first, so a lost etag race aborts the bulk save before anything is written,
+        // and the events go before the business state, so a partial save never loses an event.
+        let mut states = vec![StateRequest {
+            key: self.name.clone(),
+            value: Some(try_protobuf(&index)?),
+            etag,
+            ..Default::default()
+        }];
+
+        states.extend(event_requests);
+
+        for state in requests {
+            states.push(state.try_into_state_request(runtime.encoding())?);
+        }
+
+        runtime.save_state(states).await
+    }
+
+    /// Get the events which have not been published yet.
+    pub async fn pending<T>(&self, runtime: &mut Runtime<T>) -> Result<Vec<Event>>
+    where
+        T: tonic::client::GrpcService<tonic::body::BoxBody>,
+        T::ResponseBody: Body + HttpBody + Send + 'static,
+        T::Error: Into<StdError>,
+        <T::ResponseBody as HttpBody>::Error: Into<StdError> + Send,
+        <T::ResponseBody as HttpBody>::Data: Into<bytes::Bytes> + Send,
+    {
+        let (index, _etag) = self.index(runtime).await?;
+        let mut events = Vec::with_capacity(index.ids.len());
+
+        for id in index.ids {
+            if let Some(event) = self.event(runtime, &id).await? {
+                events.push(event);
+            }
+        }
+
+        Ok(events)
+    }
+
+    /// Publish the pending events and remove them from the outbox.
+    ///
+    /// Returns the number of published events.
+    ///
+    /// An event may be published more than once if the process crashed
+    /// after publishing it but before removing it from the outbox.
+    pub async fn relay<T>(&self, runtime: &mut Runtime<T>) -> Result<usize>
+    where
+        T: tonic::client::GrpcService<tonic::body::BoxBody>,
+        T::ResponseBody: Body + HttpBody + Send + 'static,
+        T::Error: Into<StdError>,
+        <T::ResponseBody as HttpBody>::Error: Into<StdError> + Send,
+        <T::ResponseBody as HttpBody>::Data: Into<bytes::Bytes> + Send,
+    {
+        let (index, _etag) = self.index(runtime).await?;
+
+        if index.ids.is_empty() {
+            return Ok(0);
+        }
+
+        let mut published = 0;
+
+        for id in &index.ids {
+            if let Some(Event { topic, data, .. }) = self.event(runtime, id).await? {
+                runtime.publish_event(topic, data).await?;
+                published += 1;
+            }
+
+            runtime.delete_state(self.event_key(id)).await?;
+        }
+
+        // events may be saved while relaying, so only remove the relayed ones from the latest index
+        let (mut latest, etag) = self.index(runtime).await?;
+
+        latest.ids.retain(|id| !index.ids.contains(id));
+
+        runtime
+            .save_state(Some(StateRequest {
+                key: self.name.clone(),
+                value: Some(try_protobuf(&latest)?),
+                etag,
+                ..Default::default()
+            }))
+            .await?;
+
+        Ok(published)
+    }
+
+    /// Relay the pending events in background, waiting for the `tick` future between each round.
+    ///
+    /// The events left over by a previous run are published in the first round. A failed round,
+    /// e.g. when the state store is unavailable or a save lost the etag race on the index, is
+    /// reported to `on_error` and retried after the next tick. The relay runs until the caller
+    /// drops the returned future, e.g. when the app is shutting down.
+    pub async fn run<T, F, R, E>(&self, runtime: &mut Runtime<T>, mut tick: F, mut on_error: E)
+    where
+        T: tonic::client::GrpcService<tonic::body::BoxBody>,
+        T::ResponseBody: Body + HttpBody + Send + 'static,
+        T::Error: Into<StdError>,
+        <T::ResponseBody as HttpBody>::Error: Into<StdError> + Send,
+        <T::ResponseBody as HttpBody>::Data: Into<bytes::Bytes> + Send,
+        F: FnMut() -> R,
+        R: Future<Output = ()>,
+        E: FnMut(Error),
+    {
+        loop {
+            if let Err(err) = self.relay(runtime).await {
+                on_error(err);
+            }
+
+            tick().await;
+        }
+    }
+
+    async fn index<T>(&self, runtime: &mut Runtime<T>) -> Result<(Index, String)>
+    where
+        T: tonic::client::GrpcService<tonic::body::BoxBody>,
+        T::ResponseBody: Body + HttpBody + Send + 'static,
+        T::Error: Into<StdError>,
+        <T::ResponseBody as HttpBody>::Error: Into<StdError> + Send,
+        <T::ResponseBody as HttpBody>::Data: Into<bytes::Bytes> + Send,
+    {
+        let (data, etag) = runtime.get_state(self.name.clone()).await?;
+        let index = data
+            .as_ref()
+            .map(protobuf::unpack)
+            .transpose()?
+            .unwrap_or_default();
+
+        Ok((index, etag))
+    }
+
+    async fn event<T>(&self, runtime: &mut Runtime<T>, id: &str) -> Result<Option<Event>>
+    where
+        T: tonic::client::GrpcService<tonic::body::BoxBody>,
+        T::ResponseBody: Body + HttpBody + Send + 'static,
+        T::Error: Into<StdError>,
+        <T::ResponseBody as HttpBody>::Error: Into<StdError> + Send,
+        <T::ResponseBody as HttpBody>::Data: Into<bytes::Bytes> + Send,
+    {
+        let (data, _etag) = runtime.get_state(self.event_key(id)).await?;
+
+        data.as_ref()
+            .map(protobuf::unpack)
+            .transpose()
+            .map_err(Into::into)
+    }
+}
+
+/// Generate an unique ID for the event.
+fn next_id() -> String {
+    static SEQ: AtomicUsize = AtomicUsize::new(0);
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+
+    format!(
+        "{}-{}-{}",
+        now.as_nanos(),
+        std::process::id(),
+        SEQ.fetch_add(1, Ordering::Relaxed)
+    )
+}
@@ -0,0 +1,353 @@
+//! Serve multiple services from one app.
+//!
+//! The `Router` merges the adapters generated by `#[dapr::service]`, optionally under method name
+//! prefixes, with the topic and binding events into one `DaprClient`. The method names are
+//! checked when the services are added, so a name served twice is reported at startup
+//! instead of being shadowed at runtime.
+
+use std::collections::HashMap;
+
+use prost_types::Any;
+use tonic::{Request, Response, Status};
+
+use crate::{
+    client::{
+        server::{DaprClient, DaprClientServer},
+        BindingEventEnvelope, BindingResponseEnvelope, Bindings, CloudEventEnvelope, Events,
+        GetBindingsSubscriptionsEnvelope, GetTopicSubscriptionsEnvelope, InvokeEnvelope,
+    },
+    error::{Error, IntoStatus, Result},
+};
+
+/// The methods of the service adapter generated by `#[dapr::service]`.
+pub trait ServiceMethods {
+    /// The method names and aliases accepted by the service.
+    fn methods() -> &'static [&'static str];
+}
+
+/// Dispatch the invocations to the services by method names, and the events to the handlers.
+#[derive(Default)]
+pub struct Router {
+    services: Vec<Box<dyn DaprClient>>,
+    routes: HashMap<String, (usize, &'static str)>,
+    events: Option<Box<dyn Events<Error = Status> + Send + Sync>>,
+    bindings: Option<Box<dyn Bindings<Error = Status> + Send + Sync>>,
+}
+
+impl Router {
+    /// Create an empty router.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Serve the methods of service with their own names.
+    pub fn service<S>(self, service: S) -> Result<Self>
+    where
+        S: DaprClient + ServiceMethods,
+    {
+        self.service_with_prefix("", service)
+    }
+
+    /// Serve the methods of service with the names prefixed, e.g. `orders.` for `orders.place`.
+    pub fn service_with_prefix<P, S>(mut self, prefix: P, service: S) -> Result<Self>
+    where
+        P: AsRef<str>,
+        S: DaprClient + ServiceMethods,
+    {
+        let index = self.services.len();
+
+        for &method in S::methods() {
+            let name = format!("{}{}", prefix.as_ref(), method);
+
+            if self.routes.contains_key(&name) {
+                return Err(Error::DuplicateMethod(name));
+            }
+
+            self.routes.insert(name, (index, method));
+        }
+
+        self.services.push(Box::new(service));
+
+        Ok(self)
+    }
+
+    /// Handle the topic events, replacing the previous handler.
+    pub fn events<E>(mut self, events: E) -> Self
+    where
+        E: Events + Send + Sync + 'static,
+        E::Error: IntoStatus + Send,
+    {
+        self.events = Some(Box::new(WithStatus(events)));
+        self
+    }
+
+    /// Handle the binding events, replacing the previous handler.
+    pub fn bindings<B>(mut self, bindings: B) -> Self
+    where
+        B: Bindings + Send + Sync + 'static,
+        B::Error: IntoStatus + Send,
+    {
+        self.bindings = Some(Box::new(WithStatus(bindings)));
+        self
+    }
+
+    /// The served method names, including the prefixes and aliases.
+    pub fn methods(&self) -> impl Iterator<Item = &str> {
+        self.routes.keys().map(String::as_str)
+    }
+
+    /// Create the gRPC server of the router.
+    pub fn into_server(self) -> DaprClientServer<Self> {
+        DaprClientServer::new(self)
+    }
+}
+
+#[tonic::async_trait]
+impl DaprClient for Router {
+    async fn on_invoke(
+        &self,
+        mut request: Request<InvokeEnvelope>,
+    ) -> std::result::Result<Response<Any>, Status> {
+        let (index, method) = match self.routes.get(&request.get_ref().method) {
+            Some(&route) => route,
+            None => {
+                return Err(Status::unimplemented(format!(
+                    "method `{}` not found",
+                    request.get_ref().method
+                )))
+            }
+        };
+
+        // the service dispatches the method by its own name
+        request.get_mut().method = method.to_owned();
+
+        self.services[index].on_invoke(request).await
+    }
+
+    async fn get_topic_subscriptions(
+        &self,
+        _request: Request<()>,
+    ) -> std::result::Result<Response<GetTopicSubscriptionsEnvelope>, Status> {
+        let topics = match self.events {
+            Some(ref events) => events.topic_subscriptions().await?,
+            None => vec![],
+        };
+
+        Ok(Response::new(GetTopicSubscriptionsEnvelope { topics }))
+    }
+
+    async fn on_topic_event(
+        &self,
+        request: Request<CloudEventEnvelope>,
+    ) -> std::result::Result<Response<()>, Status> {
+        match self.events {
+            Some(ref events) => events
+                .on_topic_event(request.into_inner())
+                .await
+                .map(Response::new),
+            None => Err(Status::unimplemented("Not yet implemented")),
+        }
+    }
+
+    async fn get_bindings_subscriptions(
+        &self,
+        _request: Request<()>,
+    ) -> std::result::Result<Response<GetBindingsSubscriptionsEnvelope>, Status> {
+        let bindings = match self.bindings {
+            Some(ref bindings) => bindings.bindings_subscriptions().await?,
+            None => vec![],
+        };
+
+        Ok(Response::new(GetBindingsSubscriptionsEnvelope { bindings }))
+    }
+
+    async fn on_binding_event(
+        &self,
+        request: Request<BindingEventEnvelope>,
+    ) -> std::result::Result<Response<BindingResponseEnvelope>, Status> {
+        match self.bindings {
+            Some(ref bindings) => bindings
+                .on_binding_event(request.into_inner())
+                .await
+                .map(Response::new),
+            None => Err(Status::unimplemented("Not yet implemented")),
+        }
+    }
+}
+
+/// Convert the errors of the event handlers into gRPC status.
+struct WithStatus<T>(T);
+
+#[tonic::async_trait]
+impl<E> Events for WithStatus<E>
+where
+    E: Events + Send + Sync,
+    E::Error: IntoStatus + Send,
+{
+    type Error = Status;
+
+    async fn topic_subscriptions(&self) -> std::result::Result<Vec<String>, Self::Error> {
+        self.0
+            .topic_subscriptions()
+            .await
+            .map_err(IntoStatus::into_status)
+    }
+
+    async fn on_topic_event(
+        &self,
+        event: CloudEventEnvelope,
+    ) -> std::result::Result<(), Self::Error> {
+        self.0
+            .on_topic_event(event)
+            .await
+            .map_err(IntoStatus::into_status)
+    }
+}
+
+#[tonic::async_trait]
+impl<B> Bindings for WithStatus<B>
+where
+    B: Bindings + Send + Sync,
+    B::Error: IntoStatus + Send,
+{
+    type Error = Status;
+
+    async fn bindings_subscriptions(&self) -> std::result::Result<Vec<String>, Self::Error> {
+        self.0
+            .bindings_subscriptions()
+            .await
+            .map_err(IntoStatus::into_status)
+    }
+
+    async fn on_binding_event(
+        &self,
+        event: BindingEventEnvelope,
+    ) -> std::result::Result<BindingResponseEnvelope, Self::Error> {
+        self.0
+            .on_binding_event(event)
+            .await
+            .map_err(IntoStatus::into_status)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A service which answers the invocations with its name and the dispatched method.
+    macro_rules! fake_service {
+        ($name:ident, [$($method:expr),*]) => {
+            struct $name;
+
+            impl ServiceMethods for $name {
+                fn methods() -> &'static [&'static str] {
+                    &[$($method),*]
+                }
+            }
+
+            #[tonic::async_trait]
+            impl DaprClient for $name {
+                async fn on_invoke(
+                    &self,
+                    request: Request<InvokeEnvelope>,
+                ) -> std::result::Result<Response<Any>, Status> {
+                    Ok(Response::new(Any {
+                        type_url: stringify!($name).to_owned(),
+                        value: request.into_inner().method.into_bytes(),
+                    }))
+                }
+
+                async fn get_topic_subscriptions(
+                    &self,
+                    _request: Request<()>,
+                ) -> std::result::Result<Response<GetTopicSubscriptionsEnvelope>, Status> {
+                    Err(Status::unimplemented("Not yet implemented"))
+                }
+
+                async fn on_topic_event(
+                    &self,
+                    _request: Request<CloudEventEnvelope>,
+                ) -> std::result::Result<Response<()>, Status> {
+                    Err(Status::unimplemented("Not yet implemented"))
+                }
+
+                async fn get_bindings_subscriptions(
+                    &self,
+                    _request: Request<()>,
+                ) -> std::result::Result<Response<GetBindingsSubscriptionsEnvelope>, Status> {
+                    Err(Status::unimplemented("Not yet implemented"))
+                }
+
+                async fn on_binding_event(
+                    &self,
+                    _request: Request<BindingEventEnvelope>,
+                ) -> std::result::Result<Response<BindingResponseEnvelope>, Status> {
+                    Err(Status::unimplemented("Not yet implemented"))
+                }
+            }
+        };
+    }
+
+    fake_service!(Orders, ["place", "cancel"]);
+    fake_service!(Users, ["place"]);
+
+    async fn invoke(
+        router: &Router,
+        method: &str,
+    ) -> std::result::Result<(String, String), Status> {
+        let res = router
+            .on_invoke(Request::new(InvokeEnvelope {
+                method: method.to_owned(),
+                ..Default::default()
+            }))
+            .await?
+            .into_inner();
+
+        Ok((res.type_url, String::from_utf8(res.value).unwrap()))
+    }
+
+    #[test]
+    fn duplicate_method() {
+        match Router::new().service(Orders).unwrap().service(Users) {
+            Err(Error::DuplicateMethod(name)) => assert_eq!(name, "place"),
+            res => panic!("unexpected result: {:?}", res.map(|_| ())),
+        }
+
+        match Router::new()
+            .service_with_prefix("users.", Users)
+            .unwrap()
+            .service_with_prefix("users.", Users)
+        {
+            Err(Error::DuplicateMethod(name)) => assert_eq!(name, "users.place"),
+            res => panic!("unexpected result: {:?}", res.map(|_| ())),
+        }
+    }
+
+    #[tokio::test]
+    async fn route_prefixed_method() {
+        let router = Router::new()
+            .service(Orders)
+            .unwrap()
+            .service_with_prefix("users.", Users)
+            .unwrap();
+
+        let mut methods = router.methods().collect::<Vec<_>>();
+
+        methods.sort();
+
+        assert_eq!(methods, vec!["cancel", "place", "users.place"]);
+
+        assert_eq!(
+            invoke(&router, "place").await.unwrap(),
+            ("Orders".to_owned(), "place".to_owned())
+        );
+        assert_eq!(
+            invoke(&router, "users.place").await.unwrap(),
+            ("Users".to_owned(), "place".to_owned())
+        );
+        assert_eq!(
+            invoke(&router, "users.cancel").await.unwrap_err().code(),
+            tonic::Code::Unimplemented
+        );
+    }
+}
@@ -1,7 +1,7 @@
 //! The Dapr runtime API.
 
 use std::collections::HashMap;
-use std::convert::{AsMut, AsRef};
+use std::convert::{AsMut, AsRef, TryFrom, TryInto};
 
 use prost_types::Any;
 use tonic::{
@@ -10,7 +10,7 @@ use tonic::{
 };
 
 use crate::{
-    any::IntoAny,
+    any::{self, Encoding, IntoAny},
     error::{Error, Result},
 };
 
@@ -19,18 +19,29 @@ tonic::include_proto!("dapr");
 pub type Metadata = HashMap<String, String>;
 
 /// Dapr runtime API
-#[repr(transparent)]
-pub struct Runtime<T>(client::DaprClient<T>);
+pub struct Runtime<T> {
+    client: client::DaprClient<T>,
+    encoding: Encoding,
+}
+
+impl<T: Clone> Clone for Runtime<T> {
+    fn clone(&self) -> Self {
+        Runtime {
+            client: self.client.clone(),
+            encoding: self.encoding,
+        }
+    }
+}
 
 impl<T> AsRef<client::DaprClient<T>> for Runtime<T> {
     fn as_ref(&self) -> &client::DaprClient<T> {
-        &self.0
+        &self.client
     }
 }
 
 impl<T> AsMut<client::DaprClient<T>> for Runtime<T> {
     fn as_mut(&mut self) -> &mut client::DaprClient<T> {
-        &mut self.0
+        &mut self.client
     }
 }
 
@@ -41,10 +52,27 @@ where
     D::Error: Into<StdError>,
 {
     client::DaprClient::connect(dst)
-        .map(Runtime)
+        .map(|client| Runtime {
+            client,
+            encoding: Encoding::Rust,
+        })
         .map_err(Error::from)
 }
 
+impl<T> Runtime<T> {
+    /// Set the encoding of the values sent to the runtime, `Encoding::Rust` by default,
+    /// e.g. `Encoding::WellKnown` for the apps written with the other Dapr SDKs.
+    pub fn with_encoding(mut self, encoding: Encoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// The encoding of the values sent to the runtime.
+    pub fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+}
+
 impl<T> Runtime<T>
 where
     T: tonic::client::GrpcService<tonic::body::BoxBody>,
@@ -55,7 +83,7 @@ where
 {
     /// Check if the service is ready.
     pub async fn ready(&mut self) -> Result<()> {
-        self.0.ready().await.map_err(Error::from)
+        self.client.ready().await.map_err(Error::from)
     }
 
     /// Invoke a method in a Dapr enabled app.
@@ -70,11 +98,11 @@ where
         M: Into<String>,
         D: IntoAny,
     {
-        self.0
+        self.client
             .invoke_service(Request::new(InvokeServiceEnvelope {
                 id: app_id.into(),
                 method: method_name.into(),
-                data: data.into_any(),
+                data: data.try_into_any_with(self.encoding)?,
                 ..Default::default()
             }))
             .await
@@ -92,10 +120,10 @@ where
         S: Into<String>,
         D: IntoAny,
     {
-        self.0
+        self.client
             .invoke_binding(Request::new(InvokeBindingEnvelope {
                 name: name.into(),
-                data: data.into_any(),
+                data: data.try_into_any_with(self.encoding)?,
                 ..Default::default()
             }))
             .await
@@ -111,10 +139,10 @@ where
         S: Into<String>,
         D: IntoAny,
     {
-        self.0
+        self.client
             .publish_event(Request::new(PublishEventEnvelope {
                 topic: topic.into(),
-                data: data.into_any(),
+                data: data.try_into_any_with(self.encoding)?,
             }))
             .await
             .map(|res| res.into_inner())
@@ -126,7 +154,7 @@ where
     where
         S: Into<String>,
     {
-        self.0
+        self.client
             .get_state(Request::new(GetStateEnvelope {
                 key: key.into(),
                 ..Default::default()
@@ -140,16 +168,20 @@ where
             .map_err(Error::from)
     }
 
-    /// Save an array of state objects.
+    /// Save an array of state objects, e.g. `StateRequest` or `(key, value)`.
     pub async fn save_state<I, S>(&mut self, requests: I) -> Result<()>
     where
         I: IntoIterator<Item = S>,
-        S: Into<StateRequest>,
+        S: IntoStateRequest,
     {
-        self.0
-            .save_state(Request::new(SaveStateEnvelope {
-                requests: requests.into_iter().map(|state| state.into()).collect(),
-            }))
+        let encoding = self.encoding;
+        let requests = requests
+            .into_iter()
+            .map(|state| state.try_into_state_request(encoding))
+            .collect::<std::result::Result<_, _>>()?;
+
+        self.client
+            .save_state(Request::new(SaveStateEnvelope { requests }))
             .await
             .map(|res| res.into_inner())
             .map_err(Error::from)
@@ -160,7 +192,7 @@ where
     where
         S: Into<String>,
     {
-        self.0
+        self.client
             .delete_state(Request::new(DeleteStateEnvelope {
                 key: key.into(),
                 ..Default::default()
@@ -171,25 +203,91 @@ where
     }
 }
 
-impl<'a, T> From<&'a T> for StateRequest
-where
-    T: Clone + Into<StateRequest>,
-{
-    fn from(state: &'a T) -> Self {
-        state.clone().into()
+/// The state objects saved by `Runtime::save_state`, e.g. `StateRequest` or `(key, value)`.
+pub trait IntoStateRequest {
+    /// Performs the conversion, encoding the value with the encoding of runtime.
+    fn try_into_state_request(
+        self,
+        encoding: Encoding,
+    ) -> std::result::Result<StateRequest, any::Error>;
+}
+
+impl IntoStateRequest for StateRequest {
+    fn try_into_state_request(
+        self,
+        _encoding: Encoding,
+    ) -> std::result::Result<StateRequest, any::Error> {
+        Ok(self)
     }
 }
 
-impl<K, V> From<(K, V)> for StateRequest
+impl IntoStateRequest for &StateRequest {
+    fn try_into_state_request(
+        self,
+        _encoding: Encoding,
+    ) -> std::result::Result<StateRequest, any::Error> {
+        Ok(self.clone())
+    }
+}
+
+impl<K, V> IntoStateRequest for (K, V)
 where
     K: Into<String>,
     V: IntoAny,
 {
-    fn from((key, value): (K, V)) -> Self {
-        StateRequest {
+    fn try_into_state_request(
+        self,
+        encoding: Encoding,
+    ) -> std::result::Result<StateRequest, any::Error> {
+        let (key, value) = self;
+
+        Ok(StateRequest {
             key: key.into(),
-            value: value.into_any(),
+            value: value.try_into_any_with(encoding)?,
             ..Default::default()
-        }
+        })
+    }
+}
+
+impl<K, V> IntoStateRequest for &(K, V)
+where
+    K: Clone + Into<String>,
+    V: Clone + IntoAny,
+{
+    fn try_into_state_request(
+        self,
+        encoding: Encoding,
+    ) -> std::result::Result<StateRequest, any::Error> {
+        self.clone().try_into_state_request(encoding)
+    }
+}
+
+impl<'a> From<&'a StateRequest> for StateRequest {
+    fn from(state: &'a StateRequest) -> Self {
+        state.clone()
+    }
+}
+
+impl<K, V> TryFrom<(K, V)> for StateRequest
+where
+    K: Into<String>,
+    V: IntoAny,
+{
+    type Error = any::Error;
+
+    fn try_from(state: (K, V)) -> std::result::Result<Self, Self::Error> {
+        state.try_into_state_request(Encoding::Rust)
+    }
+}
+
+impl<'a, K, V> TryFrom<&'a (K, V)> for StateRequest
+where
+    K: Clone + Into<String>,
+    V: Clone + IntoAny,
+{
+    type Error = any::Error;
+
+    fn try_from(state: &'a (K, V)) -> std::result::Result<Self, Self::Error> {
+        state.clone().try_into()
     }
 }
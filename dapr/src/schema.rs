@@ -0,0 +1,173 @@
+//! The machine-readable description of the service methods.
+//!
+//! `#[dapr::service(schema)]` describes the JSON arguments and results of the methods, so the
+//! services may be called from other languages without guessing the shape of the messages.
+//!
+//! The description is available at runtime with `DescribeService`, and may be written to a file
+//! as a JSON document or an OpenAPI 3.0 document of the Dapr invoke API.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use schemars::{
+    gen::{SchemaGenerator, SchemaSettings},
+    schema::Schema,
+    JsonSchema, Map,
+};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+/// The description of service, implemented by the generated `{Trait}Client`.
+pub trait DescribeService {
+    /// The description of service methods.
+    fn schema() -> ServiceSchema;
+}
+
+/// The description of service methods.
+///
+/// The argument and result schemas refer to the shared definitions in `#/components/schemas/`.
+#[derive(Clone, Debug, Serialize)]
+pub struct ServiceSchema {
+    /// The name of service trait.
+    pub name: String,
+    /// The methods of service.
+    pub methods: Vec<MethodSchema>,
+    /// The shared definitions of the schemas.
+    pub components: Components,
+}
+
+/// The shared definitions of the schemas.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct Components {
+    /// The schemas referred by `#/components/schemas/{name}`.
+    pub schemas: Map<String, Schema>,
+}
+
+/// The description of service method.
+#[derive(Clone, Debug, Serialize)]
+pub struct MethodSchema {
+    /// The method name on the wire.
+    pub name: String,
+    /// The old method names accepted by the service.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+    /// The schema of JSON arguments.
+    pub args: Schema,
+    /// The schema of JSON result.
+    pub result: Schema,
+}
+
+/// Create the generator shared by the methods of service.
+#[doc(hidden)]
+pub fn generator() -> SchemaGenerator {
+    SchemaGenerator::new(SchemaSettings::openapi3())
+}
+
+impl ServiceSchema {
+    /// Create the description with the methods and the definitions of generator.
+    #[doc(hidden)]
+    pub fn new<S: Into<String>>(
+        name: S,
+        methods: Vec<MethodSchema>,
+        mut gen: SchemaGenerator,
+    ) -> Self {
+        ServiceSchema {
+            name: name.into(),
+            methods,
+            components: Components {
+                schemas: gen.take_definitions(),
+            },
+        }
+    }
+
+    /// Find the method by its name or alias.
+    pub fn method(&self, name: &str) -> Option<&MethodSchema> {
+        self.methods
+            .iter()
+            .find(|method| method.name == name || method.aliases.iter().any(|alias| alias == name))
+    }
+
+    /// The description as a JSON document.
+    pub fn to_json(&self) -> serde_json::Result<Value> {
+        serde_json::to_value(self)
+    }
+
+    /// The description as an OpenAPI 3.0 document of the Dapr invoke API of the app.
+    pub fn to_openapi(&self, app_id: &str) -> serde_json::Result<Value> {
+        let paths = self
+            .methods
+            .iter()
+            .map(|method| {
+                let path = format!("/v1.0/invoke/{}/method/{}", app_id, method.name);
+                let args = serde_json::to_value(&method.args)?;
+                let result = serde_json::to_value(&method.result)?;
+                let operation = json!({
+                    "post": {
+                        "operationId": method.name,
+                        "requestBody": {
+                            "required": true,
+                            "content": {
+                                "application/json": { "schema": args },
+                            },
+                        },
+                        "responses": {
+                            "200": {
+                                "description": "OK",
+                                "content": {
+                                    "application/json": { "schema": result },
+                                },
+                            },
+                        },
+                    },
+                });
+
+                Ok((path, operation))
+            })
+            .collect::<serde_json::Result<serde_json::Map<_, _>>>()?;
+        let components = serde_json::to_value(&self.components)?;
+
+        Ok(json!({
+            "openapi": "3.0.3",
+            "info": {
+                "title": self.name,
+                "version": env!("CARGO_PKG_VERSION"),
+            },
+            "paths": paths,
+            "components": components,
+        }))
+    }
+
+    /// Write the description as a JSON document to the file.
+    pub fn write_json<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        write_pretty(path, &self.to_json()?)
+    }
+
+    /// Write the description as an OpenAPI 3.0 document to the file.
+    pub fn write_openapi<P: AsRef<Path>>(&self, path: P, app_id: &str) -> io::Result<()> {
+        write_pretty(path, &self.to_openapi(app_id)?)
+    }
+}
+
+impl MethodSchema {
+    /// Describe the method with the types of arguments and result.
+    #[doc(hidden)]
+    pub fn new<A, R>(gen: &mut SchemaGenerator, name: &str, aliases: &[&str]) -> Self
+    where
+        A: JsonSchema,
+        R: JsonSchema,
+    {
+        MethodSchema {
+            name: name.to_owned(),
+            aliases: aliases.iter().map(|&alias| alias.to_owned()).collect(),
+            args: gen.subschema_for::<A>(),
+            result: gen.subschema_for::<R>(),
+        }
+    }
+}
+
+fn write_pretty<P: AsRef<Path>>(path: P, value: &Value) -> io::Result<()> {
+    let json = serde_json::to_vec_pretty(value)?;
+
+    fs::write(path, json)
+}
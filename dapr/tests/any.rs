@@ -0,0 +1,124 @@
+use bytes::Bytes;
+use dapr::any::{self, IntoAny, Unpack, WellKnown};
+use proptest::prelude::*;
+
+proptest! {
+    #[test]
+    fn bool_round_trip(x: bool) {
+        prop_assert_eq!(x.into_any().unpack::<bool>().unwrap(), x);
+        prop_assert_eq!(WellKnown(x).into_any().unpack::<bool>().unwrap(), x);
+    }
+
+    #[test]
+    fn unit_round_trip(x: ()) {
+        prop_assert_eq!(x.into_any().unpack::<()>().unwrap(), x);
+    }
+
+    #[test]
+    fn f32_round_trip(x in proptest::num::f32::ANY) {
+        prop_assert_eq!(x.into_any().unpack::<f32>().unwrap().to_bits(), x.to_bits());
+        prop_assert_eq!(
+            WellKnown(x).into_any().unpack::<f32>().unwrap().to_bits(),
+            x.to_bits()
+        );
+    }
+
+    #[test]
+    fn f64_round_trip(x in proptest::num::f64::ANY) {
+        prop_assert_eq!(x.into_any().unpack::<f64>().unwrap().to_bits(), x.to_bits());
+        prop_assert_eq!(
+            WellKnown(x).into_any().unpack::<f64>().unwrap().to_bits(),
+            x.to_bits()
+        );
+    }
+
+    #[test]
+    fn char_round_trip(x: char) {
+        prop_assert_eq!(x.into_any().unpack::<char>().unwrap(), x);
+        prop_assert_eq!(WellKnown(x).into_any().unpack::<char>().unwrap(), x);
+    }
+
+    #[test]
+    fn bytes_round_trip(x: Vec<u8>) {
+        prop_assert_eq!(&x.clone().into_any().unpack::<Vec<u8>>().unwrap(), &x);
+        prop_assert_eq!(&x.as_slice().into_any().unpack::<Vec<u8>>().unwrap(), &x);
+        prop_assert_eq!(
+            &Bytes::from(x.clone()).into_any().unpack::<Vec<u8>>().unwrap(),
+            &x
+        );
+        prop_assert_eq!(
+            Bytes::from(x.clone()).into_any().unpack::<Bytes>().unwrap(),
+            Bytes::from(x.clone())
+        );
+        prop_assert_eq!(
+            WellKnown(Bytes::from(x.clone())).into_any().unpack::<Bytes>().unwrap(),
+            Bytes::from(x.clone())
+        );
+        prop_assert_eq!(&any::pack(x.clone()).unpack::<Vec<u8>>().unwrap(), &x);
+        prop_assert_eq!(&any::pack(&x).unpack::<Vec<u8>>().unwrap(), &x);
+        prop_assert_eq!(
+            &WellKnown(x.clone()).into_any().unpack::<Vec<u8>>().unwrap(),
+            &x
+        );
+    }
+}
+
+#[test]
+fn float_special_values_round_trip() {
+    for &x in &[
+        0.0f64,
+        -0.0,
+        f64::NAN,
+        -f64::NAN,
+        f64::INFINITY,
+        f64::NEG_INFINITY,
+    ] {
+        assert_eq!(x.into_any().unpack::<f64>().unwrap().to_bits(), x.to_bits());
+        assert_eq!(
+            (x as f32).into_any().unpack::<f32>().unwrap().to_bits(),
+            (x as f32).to_bits()
+        );
+    }
+}
+
+fn type_mismatch<T>(res: Result<T, any::Error>) -> (&'static str, String) {
+    match res {
+        Err(any::Error::TypeMismatch { expected, actual }) => (expected, actual),
+        Err(err) => panic!("unexpected error: {}", err),
+        Ok(_) => panic!("unexpected success"),
+    }
+}
+
+#[test]
+fn unpack_other_integer_is_type_mismatch() {
+    let any = 7u32.into_any().unwrap();
+
+    assert_eq!(
+        type_mismatch(any.clone().unpack::<i32>()),
+        ("i32", any.type_url.clone())
+    );
+    assert_eq!(any.unpack_lenient::<i32>().unwrap(), 7);
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn unpack_json_as_string_is_type_mismatch() {
+    let any = any::json(&vec!["apple"]).unwrap();
+
+    assert_eq!(
+        type_mismatch(any.clone().unpack::<String>()),
+        ("String", any.type_url.clone())
+    );
+    assert_eq!(any.unpack_lenient::<String>().unwrap(), r#"["apple"]"#);
+}
+
+#[test]
+fn unpack_legacy_native_endian_value() {
+    let any = dapr::prost_types::Any {
+        type_url: "rust-lang.org/i64".to_owned(),
+        value: (-2i64).to_ne_bytes().to_vec(),
+    };
+
+    assert_eq!(any.clone().unpack::<i64>().unwrap(), -2);
+    assert_eq!(type_mismatch(any.unpack::<u64>()).0, "u64");
+}
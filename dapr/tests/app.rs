@@ -0,0 +1,61 @@
+use std::net::SocketAddr;
+
+use dapr::{
+    health::{client::HealthClient, HealthCheckRequest, ServingStatus},
+    tonic::{Code, Request},
+    App, Router,
+};
+use tokio::sync::oneshot;
+
+#[tokio::test]
+async fn serve_health_checks_until_shutdown() {
+    let addr: SocketAddr = ([127, 0, 0, 1], 0).into();
+    let app = App::new(Router::new())
+        .with_app_id("shop")
+        .bind(addr)
+        .unwrap();
+    let addr = app.local_addr();
+
+    assert_ne!(addr.port(), 0);
+    assert_eq!(app.app_id(), Some("shop"));
+
+    let (shutdown, signal) = oneshot::channel::<()>();
+    let (done, stopped) = oneshot::channel();
+
+    tokio::spawn(async move {
+        let res = app
+            .serve_with_shutdown(async move {
+                let _ = signal.await;
+            })
+            .await;
+
+        let _ = done.send(res.map_err(|err| err.to_string()));
+    });
+
+    let mut health = HealthClient::connect(format!("http://{}", addr)).unwrap();
+
+    for &service in &["", "daprclient.DaprClient", "shop"] {
+        let res = health
+            .check(Request::new(HealthCheckRequest {
+                service: service.to_owned(),
+            }))
+            .await
+            .unwrap();
+
+        assert_eq!(res.get_ref().status, ServingStatus::Serving as i32);
+    }
+
+    let status = health
+        .check(Request::new(HealthCheckRequest {
+            service: "billing".to_owned(),
+        }))
+        .await
+        .unwrap_err();
+
+    assert_eq!(status.code(), Code::NotFound);
+
+    drop(health);
+    shutdown.send(()).unwrap();
+
+    assert_eq!(stopped.await.unwrap(), Ok(()));
+}
@@ -0,0 +1,105 @@
+use dapr::codec::Codec;
+
+#[cfg(feature = "msgpack")]
+#[test]
+fn msgpack_value_advertises_codec() {
+    use dapr::any::{self, IntoAny};
+
+    let value = any::msgpack::pack("hello").unwrap().into_any().unwrap();
+
+    assert_eq!(Codec::from_type_url(&value.type_url), Some(Codec::MsgPack));
+    assert_eq!(
+        Codec::from_type_url(&any::msgpack("hello").unwrap().type_url),
+        Some(Codec::MsgPack)
+    );
+}
+
+#[cfg(feature = "cbor")]
+#[test]
+fn cbor_value_advertises_codec() {
+    use dapr::any::{self, IntoAny};
+
+    let value = any::cbor::pack("hello").unwrap().into_any().unwrap();
+
+    assert_eq!(Codec::from_type_url(&value.type_url), Some(Codec::Cbor));
+    assert_eq!(
+        Codec::from_type_url(&any::cbor("hello").unwrap().type_url),
+        Some(Codec::Cbor)
+    );
+}
+
+#[test]
+fn codec_type_url() {
+    assert_eq!(
+        Codec::from_type_url(&Codec::Json.type_url("shop.Order")),
+        Some(Codec::Json)
+    );
+}
+
+#[derive(
+    Debug, PartialEq, serde::Serialize, serde::Deserialize, dapr::IntoAny, dapr::TryFromAny,
+)]
+struct Order {
+    sku: String,
+    count: u32,
+}
+
+fn order() -> Order {
+    Order {
+        sku: "apple".to_owned(),
+        count: 3,
+    }
+}
+
+#[test]
+fn json_value_advertises_codec() {
+    use dapr::{any, codec, Unpack};
+
+    let any = any::try_json(&order()).unwrap();
+
+    assert_eq!(any.type_url, "rust-lang.org/json/codec::Order");
+    assert_eq!(Codec::detect(&any, Codec::MsgPack), Codec::Json);
+    assert_eq!(codec::decode::<Order>(Codec::Json, &any).unwrap(), order());
+    assert_eq!(any.unpack::<Order>().unwrap(), order());
+}
+
+#[test]
+fn legacy_json_value_is_accepted() {
+    use dapr::{any, prost_types::Any, Unpack};
+
+    let any = Any {
+        type_url: "rust-lang.org/codec::Order".to_owned(),
+        ..any::try_json(&order()).unwrap()
+    };
+
+    assert_eq!(any.unpack::<Order>().unwrap(), order());
+}
+
+#[cfg(feature = "msgpack")]
+#[test]
+fn msgpack_round_trip() {
+    use dapr::codec;
+
+    let any = codec::encode(Codec::MsgPack, &order()).unwrap();
+
+    assert_eq!(any.type_url, "rust-lang.org/msgpack/codec::Order");
+    assert_eq!(Codec::detect(&any, Codec::Json), Codec::MsgPack);
+    assert_eq!(
+        codec::decode::<Order>(Codec::MsgPack, &any).unwrap(),
+        order()
+    );
+    assert!(codec::decode::<Order>(Codec::Cbor, &any).is_err());
+}
+
+#[cfg(feature = "cbor")]
+#[test]
+fn cbor_round_trip() {
+    use dapr::codec;
+
+    let any = codec::encode(Codec::Cbor, &order()).unwrap();
+
+    assert_eq!(any.type_url, "rust-lang.org/cbor/codec::Order");
+    assert_eq!(Codec::detect(&any, Codec::Json), Codec::Cbor);
+    assert_eq!(codec::decode::<Order>(Codec::Cbor, &any).unwrap(), order());
+    assert!(codec::decode::<Order>(Codec::Json, &any).is_err());
+}
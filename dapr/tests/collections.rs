@@ -0,0 +1,111 @@
+use std::collections::{BTreeMap, HashMap};
+
+use dapr::any::{self, IntoAny, Unpack};
+use proptest::prelude::*;
+
+proptest! {
+    #[test]
+    fn vec_round_trip(x: Vec<String>) {
+        prop_assert_eq!(x.clone().into_any().unpack::<Vec<String>>().unwrap(), x);
+    }
+
+    #[test]
+    fn nested_vec_round_trip(x: Vec<Vec<i64>>) {
+        prop_assert_eq!(x.clone().into_any().unpack::<Vec<Vec<i64>>>().unwrap(), x);
+    }
+
+    #[test]
+    fn hash_map_round_trip(x: HashMap<String, i64>) {
+        prop_assert_eq!(x.clone().into_any().unpack::<HashMap<String, i64>>().unwrap(), x);
+    }
+
+    #[test]
+    fn btree_map_round_trip(x: BTreeMap<u32, Option<String>>) {
+        prop_assert_eq!(
+            x.clone().into_any().unpack::<BTreeMap<u32, Option<String>>>().unwrap(),
+            x
+        );
+    }
+
+    #[test]
+    fn option_round_trip(x: Option<u32>) {
+        prop_assert_eq!(x.into_any().unpack::<Option<u32>>().unwrap(), x);
+        prop_assert_eq!(
+            vec![x, None].into_any().unpack::<Vec<Option<u32>>>().unwrap(),
+            vec![x, None]
+        );
+    }
+
+    #[test]
+    fn tuple_round_trip(x: (String,), y: (u8, i16, u32, i64, bool, char, String, Vec<u8>)) {
+        prop_assert_eq!(x.clone().into_any().unpack::<(String,)>().unwrap(), x);
+        prop_assert_eq!(
+            y.clone()
+                .into_any()
+                .unpack::<(u8, i16, u32, i64, bool, char, String, Vec<u8>)>()
+                .unwrap(),
+            y
+        );
+    }
+}
+
+#[test]
+fn sequence_unpacks_as_other_sequence() {
+    let any = vec![3u32, 1, 2].into_any().unwrap();
+
+    assert_eq!(
+        any.unpack::<std::collections::BTreeSet<u32>>()
+            .unwrap()
+            .into_iter()
+            .collect::<Vec<_>>(),
+        vec![1, 2, 3]
+    );
+}
+
+fn type_mismatch<T: std::fmt::Debug>(res: Result<T, any::Error>) -> &'static str {
+    match res {
+        Err(any::Error::TypeMismatch { expected, .. }) => expected,
+        res => panic!("unexpected result: {:?}", res),
+    }
+}
+
+#[test]
+fn unpack_item_as_other_type_is_type_mismatch() {
+    let any = vec![1u32, 2].into_any().unwrap();
+
+    assert_eq!(type_mismatch(any.clone().unpack::<Vec<i32>>()), "i32");
+    assert_eq!(
+        any.clone().unpack_lenient::<Vec<i32>>().unwrap(),
+        vec![1, 2]
+    );
+
+    let mut map = HashMap::new();
+
+    map.insert("apple".to_owned(), 1i64);
+
+    let any = map.into_any().unwrap();
+
+    assert_eq!(
+        type_mismatch(any.clone().unpack::<HashMap<String, u64>>()),
+        "u64"
+    );
+    assert_eq!(
+        type_mismatch(any.unpack::<HashMap<Vec<u8>, i64>>()),
+        "bytes"
+    );
+
+    let any = (1u32, "apple").into_any().unwrap();
+
+    assert_eq!(type_mismatch(any.clone().unpack::<(u32, u32)>()), "u32");
+    assert!(matches!(
+        any.unpack::<(u32,)>(),
+        Err(any::Error::Malformed("tuple"))
+    ));
+}
+
+#[test]
+fn unpack_collection_as_other_collection_is_type_mismatch() {
+    let any = vec![1u32].into_any().unwrap();
+
+    assert_eq!(type_mismatch(any.unpack::<HashMap<u32, u32>>()), "map");
+}
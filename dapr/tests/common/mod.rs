@@ -0,0 +1,157 @@
+//! An in-memory Dapr runtime for the tests.
+//!
+//! The state store keeps the values as bytes, dropping the `type_url` like the real state stores,
+//! and applies a bulk save request by request, stopping at the first etag mismatch.
+
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+use dapr::{
+    prost_types::Any,
+    runtime::{
+        server::{Dapr, DaprServer},
+        DeleteStateEnvelope, GetStateEnvelope, GetStateResponseEnvelope, PublishEventEnvelope,
+        Runtime, SaveStateEnvelope,
+    },
+    tonic::{transport::Channel, Code, Request, Response, Status},
+};
+use hyper::server::conn::AddrIncoming;
+
+#[derive(Default)]
+pub struct FakeDapr {
+    state: Mutex<HashMap<String, (Vec<u8>, u64)>>,
+    published: Mutex<Vec<PublishEventEnvelope>>,
+    race: Mutex<Option<String>>,
+    unavailable: Mutex<usize>,
+}
+
+impl FakeDapr {
+    /// Simulate a concurrent writer, which updates the key right after it was read.
+    pub fn race_on<S: Into<String>>(&self, key: S) {
+        *self.race.lock().unwrap() = Some(key.into());
+    }
+
+    /// Simulate an unavailable state store, which fails the next `count` reads.
+    pub fn fail_gets(&self, count: usize) {
+        *self.unavailable.lock().unwrap() = count;
+    }
+
+    /// The keys in the state store.
+    pub fn keys(&self) -> Vec<String> {
+        let mut keys = self
+            .state
+            .lock()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>();
+        keys.sort();
+        keys
+    }
+
+    /// The published events.
+    pub fn published(&self) -> Vec<PublishEventEnvelope> {
+        self.published.lock().unwrap().clone()
+    }
+}
+
+#[dapr::async_trait]
+impl Dapr for FakeDapr {
+    async fn publish_event(
+        &self,
+        request: Request<PublishEventEnvelope>,
+    ) -> Result<Response<()>, Status> {
+        self.published.lock().unwrap().push(request.into_inner());
+
+        Ok(Response::new(()))
+    }
+
+    async fn get_state(
+        &self,
+        request: Request<GetStateEnvelope>,
+    ) -> Result<Response<GetStateResponseEnvelope>, Status> {
+        let key = request.into_inner().key;
+
+        {
+            let mut unavailable = self.unavailable.lock().unwrap();
+
+            if *unavailable > 0 {
+                *unavailable -= 1;
+
+                return Err(Status::new(Code::Unavailable, "state store is unavailable"));
+            }
+        }
+
+        let mut state = self.state.lock().unwrap();
+        let res = match state.get(&key) {
+            Some((value, etag)) => GetStateResponseEnvelope {
+                data: Some(Any {
+                    type_url: String::new(),
+                    value: value.clone(),
+                }),
+                etag: etag.to_string(),
+            },
+            None => GetStateResponseEnvelope::default(),
+        };
+
+        if self.race.lock().unwrap().take().as_ref() == Some(&key) {
+            let entry = state.entry(key).or_default();
+            entry.1 += 1;
+        }
+
+        Ok(Response::new(res))
+    }
+
+    async fn save_state(
+        &self,
+        request: Request<SaveStateEnvelope>,
+    ) -> Result<Response<()>, Status> {
+        let mut state = self.state.lock().unwrap();
+
+        for req in request.into_inner().requests {
+            let current = state.get(&req.key).map(|(_, etag)| *etag);
+
+            if !req.etag.is_empty() && current.map(|etag| etag.to_string()) != Some(req.etag) {
+                return Err(Status::new(
+                    Code::Aborted,
+                    format!("etag mismatch of `{}`", req.key),
+                ));
+            }
+
+            let value = req.value.map(|any| any.value).unwrap_or_default();
+
+            state.insert(req.key, (value, current.unwrap_or_default() + 1));
+        }
+
+        Ok(Response::new(()))
+    }
+
+    async fn delete_state(
+        &self,
+        request: Request<DeleteStateEnvelope>,
+    ) -> Result<Response<()>, Status> {
+        self.state.lock().unwrap().remove(&request.into_inner().key);
+
+        Ok(Response::new(()))
+    }
+}
+
+/// Serve the fake runtime on a local port, and connect to it.
+pub fn serve(dapr: Arc<FakeDapr>) -> Runtime<Channel> {
+    let addr: SocketAddr = ([127, 0, 0, 1], 0).into();
+    let incoming = AddrIncoming::bind(&addr).unwrap();
+    let addr = incoming.local_addr();
+
+    tokio::spawn(async move {
+        hyper::Server::builder(incoming)
+            .http2_only(true)
+            .serve(DaprServer::from_shared(dapr))
+            .await
+            .unwrap()
+    });
+
+    dapr::connect(format!("http://{}", addr)).unwrap()
+}
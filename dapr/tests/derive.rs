@@ -0,0 +1,73 @@
+use dapr::any::{IntoAny, Unpack, WellKnown};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+mod shop {
+    #[derive(
+        Debug, PartialEq, serde::Serialize, serde::Deserialize, dapr::IntoAny, dapr::TryFromAny,
+    )]
+    pub struct Order {
+        pub id: u32,
+    }
+}
+
+mod billing {
+    #[derive(
+        Debug, PartialEq, serde::Serialize, serde::Deserialize, dapr::IntoAny, dapr::TryFromAny,
+    )]
+    pub struct Order {
+        pub id: u32,
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, dapr::IntoAny, dapr::TryFromAny)]
+#[dapr(name = "Page")]
+#[serde(bound = "")]
+struct Page<T: Serialize + DeserializeOwned> {
+    items: Vec<T>,
+}
+
+#[test]
+fn default_name_has_module_path() {
+    let any = shop::Order { id: 1 }.into_any().unwrap();
+
+    assert_eq!(any.type_url, "rust-lang.org/json/derive::shop::Order");
+    assert_eq!(
+        any.clone().unpack::<shop::Order>().unwrap(),
+        shop::Order { id: 1 }
+    );
+    assert!(any.unpack::<billing::Order>().is_err());
+}
+
+#[test]
+fn generic_type_is_named() {
+    let any = Page { items: vec![1, 2] }.into_any().unwrap();
+
+    assert_eq!(any.type_url, "rust-lang.org/json/Page");
+    assert_eq!(
+        any.unpack::<Page<i32>>().unwrap(),
+        Page { items: vec![1, 2] }
+    );
+}
+
+#[derive(Clone, PartialEq, prost_derive::Message, dapr::IntoAny, dapr::TryFromAny)]
+#[dapr(codec = "protobuf", name = "shop.Item")]
+struct Item {
+    #[prost(string, tag = "1")]
+    sku: String,
+}
+
+#[test]
+fn protobuf_message_is_well_known() {
+    let item = Item {
+        sku: "apple".to_owned(),
+    };
+
+    let any = item.clone().into_any().unwrap();
+
+    assert_eq!(any.type_url, "rust-lang.org/protobuf/shop.Item");
+
+    let any = WellKnown(item.clone()).into_any().unwrap();
+
+    assert_eq!(any.type_url, "type.googleapis.com/shop.Item");
+    assert!(any.unpack::<Item>().unwrap() == item);
+}
@@ -0,0 +1,62 @@
+use std::sync::Arc;
+
+use tokio::sync::oneshot;
+
+#[dapr::interface]
+pub trait Greeter {
+    fn greet(&self, name: String) -> String;
+
+    fn add(&self, a: u32, b: u32) -> u32;
+
+    fn reset(&self);
+}
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[tokio::test]
+async fn mock_is_shared_across_tasks() {
+    assert_send_sync::<GreeterMock>();
+
+    let mut mock = GreeterMock::new();
+
+    mock.expect_greet()
+        .called_times(2)
+        .returning(|name| Ok(format!("hello {}", name)));
+
+    let mock = Arc::new(mock);
+    let (tx, rx) = oneshot::channel();
+
+    tokio::spawn({
+        let mock = mock.clone();
+
+        async move {
+            let _ = tx.send(mock.greet("world".to_owned()).await.unwrap());
+        }
+    });
+
+    assert_eq!(rx.await.unwrap(), "hello world");
+    assert_eq!(mock.greet("dapr".to_owned()).await.unwrap(), "hello dapr");
+}
+
+#[tokio::test]
+async fn mock_passes_arguments_as_tuple() {
+    let mut mock = GreeterMock::new();
+
+    mock.expect_add()
+        .called_once()
+        .returning(|&(a, b)| Ok(a + b));
+    mock.expect_reset().called_once().returning(|_| Ok(()));
+
+    assert_eq!(mock.add(1, 2).await.unwrap(), 3);
+    mock.reset().await.unwrap();
+}
+
+#[test]
+#[should_panic(expected = "greet: Called 1 times fewer than expected")]
+fn mock_verifies_calls_on_drop() {
+    let mut mock = GreeterMock::new();
+
+    mock.expect_greet()
+        .called_once()
+        .returning(|_| Ok(String::new()));
+}
@@ -0,0 +1,132 @@
+use std::cell::Cell;
+use std::sync::Arc;
+
+use futures_util::future;
+use tokio::sync::oneshot;
+
+use dapr::{any::Unpack, outbox::Outbox};
+
+mod common;
+
+use common::FakeDapr;
+
+#[tokio::test]
+async fn relay_publishes_saved_events() {
+    let dapr = Arc::new(FakeDapr::default());
+    let mut runtime = common::serve(dapr.clone());
+    let outbox = Outbox::new("orders-outbox");
+
+    outbox
+        .save_state(
+            &mut runtime,
+            Some(("order/1", "placed")),
+            Some(("orders", "order 1 placed")),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(outbox.pending(&mut runtime).await.unwrap().len(), 1);
+    assert_eq!(outbox.relay(&mut runtime).await.unwrap(), 1);
+    assert!(outbox.pending(&mut runtime).await.unwrap().is_empty());
+
+    let published = dapr.published();
+
+    assert_eq!(published.len(), 1);
+    assert_eq!(published[0].topic, "orders");
+    assert_eq!(
+        published[0]
+            .data
+            .clone()
+            .unpack_lenient::<String>()
+            .unwrap(),
+        "order 1 placed"
+    );
+    assert_eq!(dapr.keys(), vec!["order/1", "orders-outbox"]);
+}
+
+#[tokio::test]
+async fn lost_index_race_writes_nothing() {
+    let dapr = Arc::new(FakeDapr::default());
+    let mut runtime = common::serve(dapr.clone());
+    let outbox = Outbox::new("orders-outbox");
+
+    outbox
+        .save_state(
+            &mut runtime,
+            Some(("order/1", "placed")),
+            None::<(&str, &str)>,
+        )
+        .await
+        .unwrap();
+
+    // another writer updates the index after this one has read it
+    dapr.race_on("orders-outbox");
+
+    let res = outbox
+        .save_state(
+            &mut runtime,
+            Some(("order/2", "placed")),
+            Some(("orders", "order 2 placed")),
+        )
+        .await;
+
+    assert!(res.is_err());
+    assert_eq!(dapr.keys(), vec!["order/1", "orders-outbox"]);
+
+    // the retry wins the race, and the event is relayed
+    outbox
+        .save_state(
+            &mut runtime,
+            Some(("order/2", "placed")),
+            Some(("orders", "order 2 placed")),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(outbox.relay(&mut runtime).await.unwrap(), 1);
+    assert_eq!(dapr.published().len(), 1);
+}
+
+#[tokio::test]
+async fn run_retries_failed_rounds() {
+    let dapr = Arc::new(FakeDapr::default());
+    let mut runtime = common::serve(dapr.clone());
+    let mut relay_runtime = runtime.clone();
+    let outbox = Outbox::new("orders-outbox");
+
+    outbox
+        .save_state(
+            &mut runtime,
+            Some(("order/1", "placed")),
+            Some(("orders", "order 1 placed")),
+        )
+        .await
+        .unwrap();
+
+    // the first round can't read the index
+    dapr.fail_gets(1);
+
+    let errors = Cell::new(0);
+    let rounds = Cell::new(0);
+    let (done, stop) = oneshot::channel::<()>();
+    let mut done = Some(done);
+    let run = outbox.run(
+        &mut relay_runtime,
+        || {
+            rounds.set(rounds.get() + 1);
+
+            if rounds.get() == 2 {
+                done.take().map(|done| done.send(()));
+            }
+
+            future::ready(())
+        },
+        |_| errors.set(errors.get() + 1),
+    );
+
+    future::select(Box::pin(run), stop).await;
+
+    assert_eq!(errors.get(), 1);
+    assert_eq!(dapr.published().len(), 1);
+    assert_eq!(dapr.keys(), vec!["order/1", "orders-outbox"]);
+}
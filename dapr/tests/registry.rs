@@ -0,0 +1,31 @@
+use dapr::{
+    any::{AnyRegistry, IntoAny},
+    prost_types::Any,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, dapr::IntoAny, dapr::TryFromAny)]
+struct Order {
+    id: u32,
+}
+
+#[test]
+fn handle_custom_type_url() {
+    let registry = AnyRegistry::new()
+        .handle("orders/v1", |order: Order| order.id)
+        .handle("counts/v1", |count: u64| count as u32);
+    let order = Any {
+        type_url: "orders/v1".to_owned(),
+        value: br#"{"id":42}"#.to_vec(),
+    };
+    let count = Any {
+        type_url: "counts/v1".to_owned(),
+        ..7u64.into_any().unwrap()
+    };
+
+    assert_eq!(registry.decode(order).unwrap(), 42);
+    assert_eq!(registry.decode(count).unwrap(), 7);
+    assert!(registry
+        .decode(Order { id: 1 }.into_any().unwrap())
+        .is_err());
+}
@@ -0,0 +1,52 @@
+#![cfg(feature = "schema")]
+
+use dapr::schema::DescribeService;
+use serde_json::json;
+
+#[dapr::service(schema, rename_all = "camelCase")]
+pub trait Calculator {
+    #[dapr(alias = "sum")]
+    fn add_numbers(&self, a: i32, b: i32) -> i32;
+}
+
+#[test]
+fn describe_service_as_json() {
+    let schema = CalculatorClient::<()>::schema();
+
+    assert!(schema.method("addNumbers").is_some());
+    assert!(schema.method("sum").is_some());
+    assert!(schema.method("add_numbers").is_none());
+
+    let doc = schema.to_json().unwrap();
+
+    assert_eq!(doc["name"], "Calculator");
+    assert_eq!(doc["methods"][0]["name"], "addNumbers");
+    assert_eq!(doc["methods"][0]["aliases"], json!(["sum"]));
+    assert_eq!(
+        doc["methods"][0]["args"],
+        json!({ "$ref": "#/components/schemas/CalculatorAddNumbersArgs" })
+    );
+
+    let args = &doc["components"]["schemas"]["CalculatorAddNumbersArgs"];
+
+    assert_eq!(args["type"], "object");
+    assert_eq!(args["required"], json!(["a", "b"]));
+    assert_eq!(args["properties"]["a"]["type"], "integer");
+}
+
+#[test]
+fn describe_service_as_openapi() {
+    let doc = CalculatorClient::<()>::schema().to_openapi("calc").unwrap();
+
+    assert_eq!(doc["openapi"], "3.0.3");
+    assert_eq!(doc["info"]["title"], "Calculator");
+
+    let operation = &doc["paths"]["/v1.0/invoke/calc/method/addNumbers"]["post"];
+
+    assert_eq!(operation["operationId"], "addNumbers");
+    assert_eq!(
+        operation["requestBody"]["content"]["application/json"]["schema"],
+        json!({ "$ref": "#/components/schemas/CalculatorAddNumbersArgs" })
+    );
+    assert!(doc["components"]["schemas"]["CalculatorAddNumbersResult"].is_object());
+}
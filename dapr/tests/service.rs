@@ -0,0 +1,123 @@
+#![cfg(feature = "msgpack")]
+
+use std::collections::HashMap;
+
+use dapr::{
+    client::{server::DaprClient, InvokeEnvelope},
+    codec::{self, Codec},
+    tonic::{Code, Request, Status},
+    Context, IntoStatus,
+};
+
+#[derive(Debug)]
+pub enum ShopError {
+    OutOfStock(String),
+}
+
+impl IntoStatus for ShopError {
+    fn into_status(self) -> Status {
+        match self {
+            ShopError::OutOfStock(sku) => Status::new(
+                Code::FailedPrecondition,
+                format!("`{}` is out of stock", sku),
+            ),
+        }
+    }
+}
+
+#[dapr::service(rename_all = "camelCase", codec = "msgpack")]
+pub trait Shop {
+    fn place_order(&self, ctx: &Context, sku: String, count: u32) -> Result<String, ShopError>;
+}
+
+struct MyShop;
+
+impl Shop for MyShop {
+    fn place_order(&self, ctx: &Context, sku: String, count: u32) -> Result<String, ShopError> {
+        if count == 0 {
+            return Err(ShopError::OutOfStock(sku));
+        }
+
+        ctx.set_response_metadata("x-method", ctx.method()).unwrap();
+
+        Ok(format!(
+            "{} x {} for {}",
+            count,
+            sku,
+            ctx.metadata()["tenant"]
+        ))
+    }
+}
+
+fn invoke(method: &str, sku: &str, count: u32) -> Request<InvokeEnvelope> {
+    let args = ShopPlaceOrderArgs {
+        sku: sku.to_owned(),
+        count,
+    };
+    let mut metadata = HashMap::new();
+
+    metadata.insert("tenant".to_owned(), "acme".to_owned());
+
+    Request::new(InvokeEnvelope {
+        method: method.to_owned(),
+        data: Some(codec::encode(Codec::MsgPack, &args).unwrap()),
+        metadata,
+    })
+}
+
+#[tokio::test]
+async fn invoke_renamed_method_with_context() {
+    let client = ShopClient::new(MyShop);
+
+    let response = client
+        .on_invoke(invoke("placeOrder", "apple", 3))
+        .await
+        .unwrap();
+
+    assert_eq!(response.metadata().get("x-method").unwrap(), "placeOrder");
+
+    let any = response.into_inner();
+
+    assert_eq!(Codec::from_type_url(&any.type_url), Some(Codec::MsgPack));
+
+    let ShopPlaceOrderResult(res) = codec::decode(Codec::MsgPack, &any).unwrap();
+
+    assert_eq!(res, "3 x apple for acme");
+}
+
+#[tokio::test]
+async fn original_method_name_is_not_served() {
+    let client = ShopClient::new(MyShop);
+
+    let status = client
+        .on_invoke(invoke("place_order", "apple", 3))
+        .await
+        .unwrap_err();
+
+    assert_eq!(status.code(), Code::Unimplemented);
+}
+
+#[tokio::test]
+async fn error_is_converted_into_status() {
+    let client = ShopClient::new(MyShop);
+
+    let status = client
+        .on_invoke(invoke("placeOrder", "apple", 0))
+        .await
+        .unwrap_err();
+
+    assert_eq!(status.code(), Code::FailedPrecondition);
+    assert_eq!(status.message(), "`apple` is out of stock");
+}
+
+#[tokio::test]
+async fn undecodable_arguments_are_invalid() {
+    let client = ShopClient::new(MyShop);
+    let mut request = invoke("placeOrder", "apple", 3);
+
+    request.get_mut().data = Some(codec::encode(Codec::MsgPack, &"apple").unwrap());
+
+    let status = client.on_invoke(request).await.unwrap_err();
+
+    assert_eq!(status.code(), Code::InvalidArgument);
+}
@@ -0,0 +1,126 @@
+use std::convert::TryFrom;
+use std::sync::Arc;
+
+use dapr::{
+    any::{self, Encoding, IntoAny, Unpack},
+    client::{BindingResponse, BindingResponseEnvelope},
+    prost_types::Any,
+};
+
+mod common;
+
+use common::FakeDapr;
+
+fn strip_type_url<T: IntoAny>(value: T) -> Any {
+    Any {
+        type_url: String::new(),
+        ..value.into_any().unwrap()
+    }
+}
+
+#[test]
+fn numbers_without_type_url_are_portable() {
+    assert_eq!(
+        strip_type_url(0x1234_5678u32)
+            .unpack_lenient::<u32>()
+            .unwrap(),
+        0x1234_5678
+    );
+    assert_eq!(strip_type_url(-2i64).unpack_lenient::<i64>().unwrap(), -2);
+    assert_eq!(strip_type_url(7usize).unpack_lenient::<usize>().unwrap(), 7);
+    assert_eq!(strip_type_url(1.5f64).unpack_lenient::<f64>().unwrap(), 1.5);
+    assert_eq!(strip_type_url('é').unpack_lenient::<char>().unwrap(), 'é');
+
+    // the `v1` format is little-endian on every platform
+    assert_eq!(strip_type_url(1u32).value, vec![1, 0, 0, 0]);
+}
+
+#[test]
+fn legacy_numbers_are_native_endian() {
+    let any = Any {
+        type_url: "rust-lang.org/u32".to_owned(),
+        value: 0x1234_5678u32.to_ne_bytes().to_vec(),
+    };
+
+    assert_eq!(any.unpack::<u32>().unwrap(), 0x1234_5678);
+}
+
+#[tokio::test]
+async fn numbers_round_trip_through_state_store() {
+    let dapr = Arc::new(FakeDapr::default());
+    let mut runtime = common::serve(dapr.clone());
+
+    runtime
+        .save_state(&[("u64", 0x0102_0304_0506_0708u64)])
+        .await
+        .unwrap();
+    runtime.save_state(&[("i16", -300i16)]).await.unwrap();
+
+    let (value, _) = runtime.get_state("u64").await.unwrap();
+
+    assert_eq!(
+        value.unpack_lenient::<u64>().unwrap(),
+        0x0102_0304_0506_0708
+    );
+
+    let (value, _) = runtime.get_state("i16").await.unwrap();
+
+    assert_eq!(value.unpack_lenient::<i16>().unwrap(), -300);
+}
+
+#[derive(Clone)]
+struct Unencodable;
+
+impl IntoAny for Unencodable {
+    fn try_into_any(self) -> Result<Option<Any>, any::Error> {
+        Err(any::Error::Malformed("Unencodable"))
+    }
+}
+
+#[tokio::test]
+async fn unencodable_state_is_not_saved() {
+    let dapr = Arc::new(FakeDapr::default());
+    let mut runtime = common::serve(dapr.clone());
+
+    assert!(runtime.save_state(&[("key", Unencodable)]).await.is_err());
+    assert!(dapr.keys().is_empty());
+
+    assert!(BindingResponseEnvelope::try_from(BindingResponse::data(Unencodable)).is_err());
+    assert!(BindingResponseEnvelope::try_from(
+        BindingResponse::new().save_state(&[("key", Unencodable)])
+    )
+    .is_err());
+}
+
+#[tokio::test]
+async fn runtime_publishes_with_its_encoding() {
+    let dapr = Arc::new(FakeDapr::default());
+    let mut runtime = common::serve(dapr.clone());
+
+    assert_eq!(runtime.encoding(), Encoding::Rust);
+
+    runtime.publish_event("orders", 7u32).await.unwrap();
+
+    let mut runtime = runtime.with_encoding(Encoding::WellKnown);
+
+    runtime.publish_event("orders", 7u32).await.unwrap();
+    runtime.publish_event("orders", "apple").await.unwrap();
+
+    let published = dapr
+        .published()
+        .into_iter()
+        .map(|event| event.data.unwrap())
+        .collect::<Vec<_>>();
+
+    assert_eq!(published[0].type_url, "rust-lang.org/v1/u32");
+    assert_eq!(
+        published[1].type_url,
+        "type.googleapis.com/google.protobuf.UInt32Value"
+    );
+    assert_eq!(published[1].clone().unpack::<u32>().unwrap(), 7);
+    assert_eq!(
+        published[2].type_url,
+        "type.googleapis.com/google.protobuf.StringValue"
+    );
+    assert_eq!(published[2].clone().unpack::<String>().unwrap(), "apple");
+}
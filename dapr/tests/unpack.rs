@@ -0,0 +1,96 @@
+//! The borrowing and owned unpacking of the serde codecs.
+
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+struct Order<'a> {
+    sku: &'a str,
+    count: u32,
+}
+
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+struct OwnedOrder {
+    sku: String,
+    count: u32,
+}
+
+const ORDER: Order<'static> = Order {
+    sku: "apple",
+    count: 3,
+};
+
+/// The borrowed string points into the buffer, so it was decoded without copying.
+fn is_borrowed_from(order: &Order, buf: &[u8]) -> bool {
+    buf.as_ptr_range().contains(&order.sku.as_ptr())
+}
+
+macro_rules! unpack_tests {
+    ($codec:ident, $feature:expr, $pack:path) => {
+        #[cfg(feature = $feature)]
+        mod $codec {
+            use dapr::any::$codec::{self, Unpack, UnpackOwned};
+            use dapr::bytes::Bytes;
+            use dapr::prost_types::Any;
+
+            use super::{is_borrowed_from, Order, OwnedOrder, ORDER};
+
+            fn packed() -> Any {
+                $pack(&ORDER).unwrap()
+            }
+
+            #[test]
+            fn borrow_from_any() {
+                let any = packed();
+                let order: Order = any.unpack().unwrap();
+
+                assert_eq!(order, ORDER);
+                assert!(is_borrowed_from(&order, &any.value));
+
+                let order: Order = $codec::unpack(&any).unwrap();
+
+                assert_eq!(order, ORDER);
+            }
+
+            #[test]
+            fn borrow_from_bytes() {
+                let any = packed();
+                let buf: &[u8] = &any.value;
+                let order: Order = buf.unpack().unwrap();
+
+                assert_eq!(order, ORDER);
+                assert!(is_borrowed_from(&order, buf));
+
+                let buf = Bytes::from(any.value.clone());
+                let order: Order = buf.unpack().unwrap();
+
+                assert_eq!(order, ORDER);
+                assert!(is_borrowed_from(&order, &buf));
+
+                let order: Order = $codec::unpack_bytes(&buf).unwrap();
+
+                assert_eq!(order, ORDER);
+            }
+
+            #[test]
+            fn unpack_owned_by_value() {
+                let expected = OwnedOrder {
+                    sku: "apple".to_owned(),
+                    count: 3,
+                };
+
+                // the `Any` is a temporary, which is consumed by the owned unpacking
+                let order: OwnedOrder = packed().unpack_owned().unwrap();
+
+                assert_eq!(order, expected);
+
+                let any = packed();
+                let order: OwnedOrder = (&any).unpack_owned().unwrap();
+
+                assert_eq!(order, expected);
+                assert_eq!($codec::unpack_owned::<OwnedOrder>(any).unwrap(), expected);
+            }
+        }
+    };
+}
+
+unpack_tests!(json, "json", dapr::any::try_json);
+unpack_tests!(msgpack, "msgpack", dapr::any::try_msgpack);
+unpack_tests!(cbor, "cbor", dapr::any::try_cbor);